@@ -52,6 +52,22 @@ pub(crate) struct LoggerConfig {
     pub file_appender: Option<LoggerFileAppender>,
 }
 
+impl Default for LoggerConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            pretty_backtrace: false,
+            level: Default::default(),
+            format: Default::default(),
+            time_style: Default::default(),
+            time_pattern: Default::default(),
+            with_fields: Default::default(),
+            override_filter: None,
+            file_appender: None,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, JsonSchema, Deserialize)]
 pub(crate) enum LogLevel {
     /// The "off" level.
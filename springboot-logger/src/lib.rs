@@ -6,6 +6,7 @@ use config::{Format, LogLevel, LoggerConfig, TimeStyle, WithFields};
 use nu_ansi_term::Color;
 use springboot::application::AppBuilder;
 use springboot::component::Starter;
+use springboot::config::spring::{BannerMode, SpringConfig};
 use springboot::config::ConfigRegistry;
 use std::sync::OnceLock;
 use tracing_appender::non_blocking::WorkerGuard;
@@ -26,16 +27,28 @@ pub use log::*;
 pub type BoxLayer = Box<dyn Layer<Registry> + Send + Sync + 'static>;
 
 /// Built-in Log plugin based on [tracing](https://docs.rs/tracing)
+#[springboot::starter(logger)]
 pub struct LoggerStarter;
 
 // #[async_trait]
 impl Starter for LoggerStarter {
     fn build(&self, app: &mut AppBuilder) {
-        let config = app
-            .get_config::<LoggerConfig>()
-            .expect("tracing component config load failed");
-        if config.enable {
+        let config = match app.get_config::<LoggerConfig>() {
+            Ok(config) => config,
+            Err(e) => {
+                // reported once the services are installed, the app fails to start
+                app.add_startup_check(move |_| Err(e));
+                return;
+            }
+        };
+        let banner_mode = app
+            .get_config::<SpringConfig>()
+            .map(|spring| spring.banner.mode)
+            .unwrap_or_default();
+        // the level is part of the console banner
+        if banner_mode == BannerMode::Console {
             let level = match config.level {
+                _ if !config.enable => Color::LightRed.paint("Disabled"),
                 LogLevel::Off => Color::LightRed.paint("Disabled"),
                 LogLevel::Trace => Color::Purple.paint("TRACE"),
                 LogLevel::Debug => Color::Blue.paint("DEBUG"),
@@ -44,8 +57,6 @@ impl Starter for LoggerStarter {
                 LogLevel::Error => Color::Red.paint("ERROR"),
             };
             println!("     logger: {}\n", level);
-        } else {
-            println!("     logger: {}\n", Color::LightRed.paint("Disabled"));
         }
 
         if config.pretty_backtrace {
@@ -57,10 +68,14 @@ impl Starter for LoggerStarter {
         let layers = config.config_subscriber(Default::default());
 
         let env_filter = config.build_env_filter();
-        tracing_subscriber::registry()
+        // another app of the process, such as a test, may have installed the subscriber
+        if let Err(e) = tracing_subscriber::registry()
             .with(layers)
             .with(env_filter)
-            .init();
+            .try_init()
+        {
+            log::debug!("tracing subscriber is already installed: {e}");
+        }
     }
 }
 
@@ -82,7 +97,8 @@ impl LoggerConfig {
                 let file_appender_layer = if file_logger.non_blocking {
                     let (non_blocking_file_appender, work_guard) =
                         tracing_appender::non_blocking(file_appender);
-                    NONBLOCKING_WORK_GUARD_KEEP.set(work_guard).unwrap();
+                    // the guard of the first logger is kept
+                    let _ = NONBLOCKING_WORK_GUARD_KEEP.set(work_guard);
                     self.build_fmt_layer(non_blocking_file_appender, &file_logger.format, false)
                 } else {
                     self.build_fmt_layer(file_appender, &file_logger.format, false)
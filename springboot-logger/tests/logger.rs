@@ -0,0 +1,19 @@
+use springboot::testing::TestApp;
+use springboot_logger::LoggerStarter;
+
+// the tracing subscriber is global, building a second app must not panic
+#[tokio::test]
+async fn test_logger_installed_twice() {
+    for _ in 0..2 {
+        let app = TestApp::build(TestApp::builder("[logger]\nlevel = \"info\"").logger(LoggerStarter))
+            .await
+            .unwrap();
+        app.shutdown().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_config_fails_build() {
+    let app = TestApp::build(TestApp::builder("[logger]\nlevel = 42").logger(LoggerStarter)).await;
+    assert!(app.is_err());
+}
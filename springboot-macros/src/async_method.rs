@@ -0,0 +1,167 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{FnArg, ImplItemFn, LitStr, Pat, ReturnType, Token};
+
+/// `pool = "name"`
+struct AsyncMethodArgs {
+    pool: LitStr,
+}
+
+impl Parse for AsyncMethodArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(Self {
+                pool: LitStr::new("default", input.span()),
+            });
+        }
+        let name = input.parse::<syn::Ident>()?;
+        if name != "pool" {
+            return Err(syn::Error::new_spanned(
+                name,
+                "invalid async method definition, expected #[async_method] or #[async_method(pool = \"name\")]",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let pool = input.parse::<LitStr>()?;
+        Ok(Self { pool })
+    }
+}
+
+struct AsyncMethod {
+    pool: LitStr,
+
+    /// AST of the method being annotated.
+    ast: ImplItemFn,
+}
+
+impl AsyncMethod {
+    fn new(args: AsyncMethodArgs, ast: ImplItemFn) -> syn::Result<Self> {
+        if ast.sig.asyncness.is_none() {
+            return Err(syn::Error::new_spanned(
+                ast.sig.fn_token,
+                "only support async fn as async method",
+            ));
+        }
+        match ast.sig.inputs.first() {
+            Some(FnArg::Receiver(receiver))
+                if receiver.reference.is_some() && receiver.mutability.is_none() => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &ast.sig,
+                    "async method must take `&self`, the service is cloned into the task",
+                ))
+            }
+        }
+        for arg in ast.sig.inputs.iter().skip(1) {
+            if let FnArg::Typed(pat_type) = arg {
+                if borrows(pat_type.ty.to_token_stream()) {
+                    return Err(syn::Error::new_spanned(
+                        &pat_type.ty,
+                        "async method can't take borrowed arguments, the arguments are moved into the task",
+                    ));
+                }
+            }
+        }
+        Ok(Self {
+            pool: args.pool,
+            ast,
+        })
+    }
+}
+
+/// Whether the type holds a reference or a lifetime other than `'static`
+fn borrows(ty: TokenStream) -> bool {
+    let mut tokens = ty.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let borrowed = match token {
+            // a reference without a lifetime, the lifetime of `&'a T` is checked next
+            TokenTree::Punct(punct) if punct.as_char() == '&' => {
+                !matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '\'')
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                !matches!(tokens.peek(), Some(TokenTree::Ident(i)) if i == "static")
+            }
+            TokenTree::Group(group) => borrows(group.stream()),
+            _ => false,
+        };
+        if borrowed {
+            return true;
+        }
+    }
+    false
+}
+
+impl ToTokens for AsyncMethod {
+    fn to_tokens(&self, output: &mut TokenStream) {
+        let Self { pool, ast } = self;
+        let name = &ast.sig.ident;
+        let inner_name = format_ident!("__async_method_{}", name);
+
+        let mut inner = ast.clone();
+        inner.sig.ident = inner_name.clone();
+        inner.vis = syn::Visibility::Inherited;
+        inner.attrs.retain(|attr| !attr.path().is_ident("doc"));
+
+        let mut outer_sig = ast.sig.clone();
+        outer_sig.asyncness = None;
+        let mut arg_names = vec![];
+        for (i, arg) in outer_sig.inputs.iter_mut().skip(1).enumerate() {
+            if let FnArg::Typed(pat_type) = arg {
+                let arg_name = match pat_type.pat.as_ref() {
+                    Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+                    _ => format_ident!("__arg{}", i),
+                };
+                *pat_type.pat = syn::parse_quote!(#arg_name);
+                arg_names.push(arg_name);
+            }
+        }
+        let output_ty = match &ast.sig.output {
+            ReturnType::Default => quote!(()),
+            ReturnType::Type(_, ty) => ty.to_token_stream(),
+        };
+        outer_sig.output = syn::parse_quote!(-> ::springboot::executor::AsyncResult<#output_ty>);
+        let attrs = &ast.attrs;
+        let vis = &ast.vis;
+
+        output.extend(quote! {
+            #(#attrs)*
+            #vis #outer_sig {
+                let __this = ::std::clone::Clone::clone(self);
+                let __method = ::springboot::executor::AsyncMethod {
+                    owner: ::std::any::type_name::<Self>(),
+                    name: stringify!(#name),
+                    pool: #pool,
+                };
+                ::springboot::executor::dispatch(__method, async move {
+                    __this.#inner_name(#(#arg_names),*).await
+                })
+            }
+
+            #[doc(hidden)]
+            #inner
+        });
+    }
+}
+
+pub(crate) fn with_async_method(args: TokenStream, ast: ImplItemFn) -> syn::Result<TokenStream> {
+    let args = syn::parse2::<AsyncMethodArgs>(args)?;
+    Ok(AsyncMethod::new(args, ast)?.into_token_stream())
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::borrows;
+    use quote::quote;
+
+    #[test]
+    fn test_borrows() {
+        assert!(borrows(quote!(&str)));
+        assert!(borrows(quote!(&'a mut Post)));
+        assert!(borrows(quote!(Option<&[u8]>)));
+        assert!(borrows(quote!(std::borrow::Cow<'a, str>)));
+        assert!(!borrows(quote!(&'static str)));
+        assert!(!borrows(quote!(Box<dyn Fn() + 'static>)));
+        assert!(!borrows(quote!(Vec<(String, u64)>)));
+    }
+}
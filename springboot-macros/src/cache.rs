@@ -0,0 +1,181 @@
+use crate::util::returns_result;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{FnArg, ImplItemFn, LitBool, LitStr, Pat, ReturnType, Token};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CacheOp {
+    Cacheable,
+    Put,
+    Evict,
+}
+
+impl CacheOp {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Cacheable => "cacheable",
+            Self::Put => "cache_put",
+            Self::Evict => "cache_evict",
+        }
+    }
+}
+
+/// `"cache"[, key = "expr"][, all_entries = true]`
+struct CacheArgs {
+    cache: LitStr,
+    key: Option<syn::Expr>,
+    all_entries: bool,
+}
+
+impl Parse for CacheArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let cache = input.parse::<LitStr>()?;
+        let mut args = Self {
+            cache,
+            key: None,
+            all_entries: false,
+        };
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let name = input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            if name == "key" {
+                args.key = Some(input.parse::<LitStr>()?.parse()?);
+            } else if name == "all_entries" {
+                args.all_entries = input.parse::<LitBool>()?.value;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "unknown attribute, expected `key = \"expr\"` or `all_entries = true`",
+                ));
+            }
+        }
+        Ok(args)
+    }
+}
+
+struct Cached {
+    op: CacheOp,
+    args: CacheArgs,
+
+    /// AST of the function being annotated.
+    ast: ImplItemFn,
+}
+
+impl Cached {
+    fn new(op: CacheOp, args: CacheArgs, ast: ImplItemFn) -> syn::Result<Self> {
+        if ast.sig.asyncness.is_none() {
+            return Err(syn::Error::new_spanned(
+                ast.sig.fn_token,
+                format!("only support async fn as {}", op.name()),
+            ));
+        }
+        if args.all_entries && op != CacheOp::Evict {
+            return Err(syn::Error::new_spanned(
+                &args.cache,
+                "`all_entries` is only supported by cache_evict",
+            ));
+        }
+        if args.key.is_none() && !args.all_entries {
+            for arg in &ast.sig.inputs {
+                if let FnArg::Typed(pat_type) = arg {
+                    if !matches!(pat_type.pat.as_ref(), Pat::Ident(_)) {
+                        return Err(syn::Error::new_spanned(
+                            &pat_type.pat,
+                            "the default key only supports plain arguments, set `key = \"expr\"`",
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(Self { op, args, ast })
+    }
+
+    /// Path of the function and Debug of the arguments if no key is given,
+    /// so the functions sharing a cache don't read each other's entries
+    fn key(&self) -> TokenStream {
+        if let Some(key) = &self.args.key {
+            return quote!(::std::string::ToString::to_string(&(#key)));
+        }
+        let sig = &self.ast.sig;
+        let owner = match sig.receiver() {
+            Some(_) => quote!(::std::any::type_name::<Self>()),
+            None => quote!(::std::module_path!()),
+        };
+        let name = &sig.ident;
+        let args = sig.inputs.iter().filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => Some(&pat_type.pat),
+            FnArg::Receiver(_) => None,
+        });
+        quote!(::std::format!("{}::{}{:?}", #owner, ::std::stringify!(#name), (#(&#args,)*)))
+    }
+}
+
+impl ToTokens for Cached {
+    fn to_tokens(&self, output: &mut TokenStream) {
+        let Self { op, args, ast } = self;
+        let cache = &args.cache;
+        let ImplItemFn {
+            attrs,
+            vis,
+            defaultness,
+            sig,
+            block,
+        } = ast;
+        let output_ty = match &sig.output {
+            ReturnType::Default => quote!(()),
+            ReturnType::Type(_, ty) => ty.to_token_stream(),
+        };
+        let result = returns_result(&sig.output);
+        let key = self.key();
+        let call = quote!(async move #block);
+        let body = match op {
+            CacheOp::Cacheable | CacheOp::Put => {
+                let value = if result {
+                    quote!(|r: &#output_ty| r.as_ref().ok())
+                } else {
+                    quote!(|r: &#output_ty| ::std::option::Option::Some(r))
+                };
+                if *op == CacheOp::Cacheable {
+                    let wrap = if result {
+                        quote!(::std::result::Result::Ok)
+                    } else {
+                        quote!(|v| v)
+                    };
+                    quote!(::springboot::cache::cacheable(#cache, #key, #call, #value, #wrap).await)
+                } else {
+                    quote!(::springboot::cache::cache_put(#cache, #key, #call, #value).await)
+                }
+            }
+            CacheOp::Evict => {
+                let key = if args.all_entries {
+                    quote!(::std::option::Option::None)
+                } else {
+                    quote!(::std::option::Option::Some(#key))
+                };
+                let succeeded = if result {
+                    quote!(|r: &#output_ty| r.is_ok())
+                } else {
+                    quote!(|_: &#output_ty| true)
+                };
+                quote!(::springboot::cache::cache_evict(#cache, #key, #call, #succeeded).await)
+            }
+        };
+
+        output.extend(quote! {
+            #(#attrs)*
+            #vis #defaultness #sig {
+                #body
+            }
+        });
+    }
+}
+
+pub(crate) fn with_cache(op: CacheOp, args: TokenStream, ast: ImplItemFn) -> syn::Result<TokenStream> {
+    let args = syn::parse2::<CacheArgs>(args)?;
+    Ok(Cached::new(op, args, ast)?.into_token_stream())
+}
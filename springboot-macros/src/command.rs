@@ -0,0 +1,143 @@
+use crate::input_and_compile_error;
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, ToTokens};
+
+struct Command {
+    /// Name used to select the command from the command line.
+    command_name: syn::LitStr,
+
+    /// AST of the command function being annotated.
+    ast: syn::ItemFn,
+
+    /// Types of the function arguments, resolved from the app.
+    arg_types: Vec<syn::Type>,
+
+    /// The doc comment attributes to copy to generated struct, if any.
+    doc_attributes: Vec<syn::Attribute>,
+}
+
+impl Command {
+    fn new(command_name: syn::LitStr, ast: syn::ItemFn) -> syn::Result<Self> {
+        if command_name.value().is_empty() {
+            return Err(syn::Error::new_spanned(
+                command_name,
+                "command name should not be empty",
+            ));
+        }
+        if ast.sig.asyncness.is_none() {
+            return Err(syn::Error::new_spanned(
+                ast.sig.fn_token,
+                "only support async fn as command",
+            ));
+        }
+        let arg_types = ast
+            .sig
+            .inputs
+            .iter()
+            .map(|arg| match arg {
+                syn::FnArg::Typed(pat_type) => Ok(pat_type.ty.as_ref().clone()),
+                syn::FnArg::Receiver(receiver) => Err(syn::Error::new_spanned(
+                    receiver,
+                    "command function can't have a self receiver",
+                )),
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let doc_attributes = ast
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .cloned()
+            .collect();
+
+        Ok(Self {
+            command_name,
+            ast,
+            arg_types,
+            doc_attributes,
+        })
+    }
+}
+
+impl ToTokens for Command {
+    fn to_tokens(&self, output: &mut TokenStream2) {
+        let Self {
+            command_name,
+            ast,
+            arg_types,
+            doc_attributes,
+        } = self;
+        let name = &ast.sig.ident;
+        let vis = &ast.vis;
+        let arg_names: Vec<syn::Ident> = (0..arg_types.len())
+            .map(|i| format_ident!("__arg{}", i))
+            .collect();
+
+        let stream = quote! {
+            #(#doc_attributes)*
+            #[allow(non_camel_case_types, missing_docs)]
+            #vis struct #name;
+
+            #[::springboot::async_trait]
+            impl ::springboot::runner::CommandRegistrar for #name {
+                fn name(&self) -> &'static str {
+                    #command_name
+                }
+
+                async fn run(
+                    &self,
+                    __app: ::std::sync::Arc<::springboot::App>,
+                    __args: ::springboot::runner::ApplicationArguments,
+                ) -> ::springboot::error::Result<i32> {
+                    #ast
+                    #(
+                        let #arg_names = <#arg_types as ::springboot::runner::FromApp>::from_app(&__app, &__args)?;
+                    )*
+                    Ok(::springboot::runner::ExitStatus::exit_code(#name(#(#arg_names),*).await))
+                }
+            }
+
+            ::springboot::submit_command!(#name);
+        };
+
+        output.extend(stream);
+    }
+}
+
+pub(crate) fn with_command(args: TokenStream, input: TokenStream) -> TokenStream {
+    if args.is_empty() {
+        return input_and_compile_error(
+            input,
+            syn::Error::new(
+                Span::call_site(),
+                "missing arguments for command macro, expected: #[command(\"name\")]",
+            ),
+        );
+    }
+
+    let command_name = match syn::parse::<syn::LitStr>(args) {
+        Ok(name) => name,
+        Err(err) => {
+            return input_and_compile_error(
+                input,
+                syn::Error::new(
+                    err.span(),
+                    "argument to command macro is not a string literal, expected: #[command(\"name\")]",
+                ),
+            )
+        }
+    };
+
+    let ast = match syn::parse::<syn::ItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        // on parse error, make IDEs happy; see fn docs
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match Command::new(command_name, ast) {
+        Ok(command) => command.into_token_stream().into(),
+        // on macro related error, make IDEs happy; see fn docs
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
@@ -0,0 +1,72 @@
+use crate::route::{Route, RouteArgs};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+
+pub(crate) fn with_controller(args: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
+    match syn::parse2::<syn::Item>(input)? {
+        syn::Item::Struct(item) => controller_struct(args, item),
+        syn::Item::Impl(item) => controller_impl(args, item),
+        _ => Err(syn::Error::new(
+            Span::call_site(),
+            "#[controller] macro must be attached to a struct or its impl block",
+        )),
+    }
+}
+
+/// `#[controller("/prefix")] struct` is installed as a service
+fn controller_struct(args: TokenStream, item: syn::ItemStruct) -> syn::Result<TokenStream> {
+    let args = syn::parse2::<RouteArgs>(args).map_err(|err| {
+        syn::Error::new(
+            err.span(),
+            "argument to controller macro is not a string literal, expected: #[controller(\"/prefix\")]",
+        )
+    })?;
+    if let Some(option) = args.options.first() {
+        return Err(syn::Error::new_spanned(&option.path, "controller only accepts a path prefix"));
+    }
+    let prefix = args.path.value();
+    if prefix.ends_with('/') {
+        return Err(syn::Error::new_spanned(&args.path, "controllers should not have trailing slashes"));
+    }
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&item.generics, "controller can not be generic"));
+    }
+    let name = &item.ident;
+    Ok(quote! {
+        #[derive(::std::clone::Clone, ::springboot::component::service::Service)]
+        #item
+
+        impl ::springboot_web::controller::Controller for #name {
+            const PREFIX: &'static str = #prefix;
+        }
+    })
+}
+
+/// `#[controller] impl` registers the methods with route attributes as handlers
+fn controller_impl(args: TokenStream, mut item: syn::ItemImpl) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        return Err(syn::Error::new_spanned(
+            args,
+            "the prefix belongs to the controller struct, expected: #[controller] impl",
+        ));
+    }
+    if let Some((_, trait_, _)) = &item.trait_ {
+        return Err(syn::Error::new_spanned(trait_, "controller handlers must be inherent methods"));
+    }
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&item.generics, "controller can not be generic"));
+    }
+    let mut routes = vec![];
+    for impl_item in &mut item.items {
+        if let syn::ImplItem::Fn(method) = impl_item {
+            if let Some(route) = Route::controller(&item.self_ty, method)? {
+                routes.push(route);
+            }
+        }
+    }
+    let mut output = item.into_token_stream();
+    for route in routes {
+        route.to_tokens(&mut output);
+    }
+    Ok(output)
+}
@@ -0,0 +1,74 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{FnArg, ItemFn};
+
+struct ExceptionHandler {
+    error: syn::Type,
+
+    /// AST of the function being annotated.
+    ast: ItemFn,
+}
+
+impl ExceptionHandler {
+    fn new(error: syn::Type, ast: ItemFn) -> syn::Result<Self> {
+        if let Some(asyncness) = ast.sig.asyncness {
+            return Err(syn::Error::new_spanned(
+                asyncness,
+                "exception handler must be a sync fn, it runs while converting the error into a response",
+            ));
+        }
+        if ast.sig.inputs.len() != 1 || matches!(ast.sig.inputs.first(), Some(FnArg::Receiver(_))) {
+            return Err(syn::Error::new_spanned(
+                &ast.sig,
+                "exception handler must take a reference to the error as its only argument",
+            ));
+        }
+        Ok(Self { error, ast })
+    }
+}
+
+impl ToTokens for ExceptionHandler {
+    fn to_tokens(&self, output: &mut TokenStream) {
+        let Self { error, ast } = self;
+        let name = &ast.sig.ident;
+        let vis = &ast.vis;
+        let doc_attributes = ast
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+
+        output.extend(quote! {
+            #(#doc_attributes)*
+            #[allow(non_camel_case_types, missing_docs)]
+            #vis struct #name;
+
+            impl ::springboot_web::exception::ExceptionHandler for #name {
+                fn name(&self) -> &'static str {
+                    stringify!(#name)
+                }
+
+                fn error_type(&self) -> &'static str {
+                    ::std::any::type_name::<#error>()
+                }
+
+                fn handle(
+                    &self,
+                    __error: &::springboot_web::anyhow::Error,
+                ) -> ::std::option::Option<::springboot_web::axum::response::Response> {
+                    #ast
+                    let __error = __error.downcast_ref::<#error>()?;
+                    ::std::option::Option::Some(
+                        ::springboot_web::axum::response::IntoResponse::into_response(#name(__error)),
+                    )
+                }
+            }
+
+            ::springboot_web::submit_exception_handler!(#name);
+        });
+    }
+}
+
+pub(crate) fn with_exception_handler(args: TokenStream, ast: ItemFn) -> syn::Result<TokenStream> {
+    let error = syn::parse2::<syn::Type>(args)?;
+    Ok(ExceptionHandler::new(error, ast)?.into_token_stream())
+}
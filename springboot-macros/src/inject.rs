@@ -47,7 +47,6 @@ enum InjectableAttr {
 }
 
 struct Injectable {
-    is_prototype: bool,
     ty: InjectableType,
     field_name: syn::Ident,
 }
@@ -56,11 +55,7 @@ impl Injectable {
     fn new(field: syn::Field, is_prototype: bool) -> syn::Result<Self> {
         let ty = Self::compute_type(&field, is_prototype)?;
         let field_name = field.ident.ok_or_else(inject_error_tip)?;
-        Ok(Self {
-            is_prototype,
-            ty,
-            field_name,
-        })
+        Ok(Self { ty, field_name })
     }
 
     fn compute_type(field: &syn::Field, is_prototype: bool) -> syn::Result<InjectableType> {
@@ -159,11 +154,7 @@ impl InjectableAttr {
 
 impl ToTokens for Injectable {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let Self {
-            is_prototype,
-            ty,
-            field_name,
-        } = self;
+        let Self { ty, field_name } = self;
         match ty {
             InjectableType::Option => {
                 tokens.extend(quote! {
@@ -171,48 +162,24 @@ impl ToTokens for Injectable {
                 });
             }
             InjectableType::Component(type_path) => {
-                if *is_prototype {
-                    tokens.extend(quote! {
-                        let #field_name = ::springboot::App::global().try_get_component::<#type_path>()?;
-                    });
-                } else {
-                    tokens.extend(quote! {
-                        let #field_name = app.try_get_component::<#type_path>()?;
-                    });
-                }
+                tokens.extend(quote! {
+                    let #field_name = app.try_get_component::<#type_path>()?;
+                });
             }
             InjectableType::Config(type_path) => {
-                if *is_prototype {
-                    tokens.extend(quote! {
-                        let #field_name = ::springboot::App::global().get_config::<#type_path>()?;
-                    });
-                } else {
-                    tokens.extend(quote! {
-                        let #field_name = app.get_config::<#type_path>()?;
-                    });
-                }
+                tokens.extend(quote! {
+                    let #field_name = app.get_config::<#type_path>()?;
+                });
             }
             InjectableType::ComponentRef(type_path) => {
-                if *is_prototype {
-                    tokens.extend(quote! {
-                        let #field_name = ::springboot::App::global().try_get_component_ref::<#type_path>()?;
-                    });
-                } else {
-                    tokens.extend(quote! {
-                        let #field_name = app.try_get_component_ref::<#type_path>()?;
-                    });
-                }
+                tokens.extend(quote! {
+                    let #field_name = app.try_get_component_ref::<#type_path>()?;
+                });
             }
             InjectableType::ConfigRef(type_path) => {
-                if *is_prototype {
-                    tokens.extend(quote! {
-                        let #field_name = ::springboot::config::ConfigRef::new(::springboot::App::global().get_config::<#type_path>()?);
-                    });
-                } else {
-                    tokens.extend(quote! {
-                        let #field_name = ::springboot::config::ConfigRef::new(app.get_config::<#type_path>()?);
-                    });
-                }
+                tokens.extend(quote! {
+                    let #field_name = ::springboot::config::ConfigRef::new(app.get_config::<#type_path>()?);
+                });
             }
             InjectableType::FuncCall(func_call) => {
                 tokens.extend(quote! {
@@ -313,8 +280,11 @@ impl ToTokens for Service {
         let output = match prototype {
             Some(Prototype { build }) => {
                 let fn_name = syn::Ident::new(&build.value(), build.span());
+                let fn_name_with =
+                    syn::Ident::new(&format!("{}_with", build.value()), build.span());
                 let (args, fields): (Vec<&Injectable>, Vec<&Injectable>) =
                     fields.iter().partition(|f| f.ty.is_arg());
+                let arg_names: Vec<&syn::Ident> = args.iter().map(|f| &f.field_name).collect();
                 let syn::Generics {
                     lt_token,
                     params,
@@ -323,9 +293,17 @@ impl ToTokens for Service {
                 } = generics;
                 quote! {
                     impl #lt_token #params #gt_token #ident #generics {
+                        /// Build with the components of the current app, see `App::current`
                         pub fn #fn_name(#(#args),*) -> ::springboot::error::Result<Self> {
-                            use ::springboot::component::ComponentRegistry;
-                            use ::springboot::config::ConfigRegistry;
+                            let app = ::springboot::App::try_current()?;
+                            Self::#fn_name_with(&*app, #(#arg_names),*)
+                        }
+
+                        /// Build with the components of the given registry
+                        pub fn #fn_name_with<R>(app: &R, #(#args),*) -> ::springboot::error::Result<Self>
+                        where
+                            R: ::springboot::component::ComponentRegistry + ::springboot::config::ConfigRegistry
+                        {
                             #(#fields)*
                             Ok(Self { #(#field_names),* })
                         }
@@ -335,6 +313,14 @@ impl ToTokens for Service {
             None => {
                 let service_registrar =
                     syn::Ident::new(&format!("__ServiceRegistrarFor_{ident}"), ident.span());
+                let dependencies = fields.iter().filter_map(|f| match &f.ty {
+                    InjectableType::Component(path) | InjectableType::ComponentRef(path) => Some(path),
+                    _ => None,
+                });
+                let configs = fields.iter().filter_map(|f| match &f.ty {
+                    InjectableType::Config(path) | InjectableType::ConfigRef(path) => Some(path),
+                    _ => None,
+                });
                 quote! {
                     impl ::springboot::component::service::Service for #ident {
                         fn build<R>(app: &R) -> ::springboot::error::Result<Self>
@@ -350,10 +336,26 @@ impl ToTokens for Service {
                     impl ::springboot::component::service::ServiceRegistrar for #service_registrar{
                         fn install_service(&self, app: &mut ::springboot::application::AppBuilder)->::springboot::error::Result<()> {
                             use ::springboot::component::MutableComponentRegistry;
-                            let component=#ident::build(app)?;
+                            if app.is_component_overridden::<#ident>() {
+                                return Ok(());
+                            }
+                            app.describe_component::<#ident>(
+                                ::springboot::component::graph::ComponentOrigin::Service,
+                                vec![#(<#configs as ::springboot::config::Configurable>::config_prefix()),*],
+                                self.dependencies(),
+                            );
+                            let component=<#ident as ::springboot::component::service::Service>::build(app)?;
                             app.add_component(component);
                             Ok(())
                         }
+
+                        fn name(&self) -> &'static str {
+                            ::std::any::type_name::<#ident>()
+                        }
+
+                        fn dependencies(&self) -> ::std::vec::Vec<&'static str> {
+                            vec![#(::std::any::type_name::<#dependencies>()),*]
+                        }
                     }
                     ::springboot::submit_service!(#service_registrar);
                 }
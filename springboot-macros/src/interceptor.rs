@@ -0,0 +1,211 @@
+use crate::util::returns_result;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{FnArg, ImplItemFn, Pat, Token, Type};
+
+/// `#[around(Interceptor)]`, the impl block adds `owner = Type`
+struct AroundArgs {
+    interceptor: syn::Path,
+    owner: Option<Type>,
+}
+
+impl Parse for AroundArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let interceptor = input.parse()?;
+        let mut owner = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let ident = input.parse::<syn::Ident>()?;
+            if ident != "owner" {
+                return Err(syn::Error::new_spanned(ident, "expected `owner = Type`"));
+            }
+            input.parse::<Token![=]>()?;
+            owner = Some(input.parse()?);
+        }
+        Ok(Self { interceptor, owner })
+    }
+}
+
+struct Around {
+    /// This interceptor and the ones of the `#[around]` attributes below it, outermost first.
+    interceptors: Vec<syn::Path>,
+    /// The component whose method this is, none for functions
+    owner: Option<Type>,
+    arg_names: Vec<syn::Ident>,
+    arg_pats: Vec<syn::PatIdent>,
+
+    /// AST of the function being annotated.
+    ast: ImplItemFn,
+}
+
+impl Around {
+    fn new(args: AroundArgs, mut ast: ImplItemFn) -> syn::Result<Self> {
+        if ast.sig.asyncness.is_none() {
+            return Err(syn::Error::new_spanned(
+                ast.sig.fn_token,
+                "only support async fn as intercepted method",
+            ));
+        }
+        if !returns_result(&ast.sig.output) {
+            return Err(syn::Error::new_spanned(
+                &ast.sig,
+                "intercepted method must return a `Result` whose error implements `From<springboot::error::AppError>`",
+            ));
+        }
+        let AroundArgs { interceptor, owner } = args;
+        if owner.is_none() {
+            if let Some(FnArg::Receiver(receiver)) = ast.sig.inputs.first() {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "intercepted method needs `#[around]` on its impl block, which names the owner component",
+                ));
+            }
+        }
+        let mut arg_names = vec![];
+        let mut arg_pats = vec![];
+        for arg in &ast.sig.inputs {
+            if let FnArg::Typed(pat_type) = arg {
+                match pat_type.pat.as_ref() {
+                    Pat::Ident(pat_ident) => {
+                        arg_names.push(pat_ident.ident.clone());
+                        arg_pats.push(pat_ident.clone());
+                    }
+                    pat => {
+                        return Err(syn::Error::new_spanned(
+                            pat,
+                            "intercepted method only supports plain arguments",
+                        ))
+                    }
+                }
+            }
+        }
+        // the stacked attributes are expanded here, macros would make the first one the innermost
+        let mut interceptors = vec![interceptor];
+        let mut attrs = vec![];
+        for attr in std::mem::take(&mut ast.attrs) {
+            if attr.path().segments.last().is_some_and(|segment| segment.ident == "around") {
+                interceptors.push(attr.parse_args::<AroundArgs>()?.interceptor);
+            } else {
+                attrs.push(attr);
+            }
+        }
+        ast.attrs = attrs;
+        Ok(Self {
+            interceptors,
+            owner,
+            arg_names,
+            arg_pats,
+            ast,
+        })
+    }
+}
+
+impl ToTokens for Around {
+    fn to_tokens(&self, output: &mut TokenStream) {
+        let Self {
+            interceptors,
+            owner: owner_ty,
+            arg_names,
+            arg_pats,
+            ast,
+        } = self;
+        let ImplItemFn {
+            attrs,
+            vis,
+            defaultness,
+            sig,
+            block,
+        } = ast;
+        let name = &sig.ident;
+        // the arguments are moved into the body, which keeps their `mut`
+        let mut sig = sig.clone();
+        for arg in sig.inputs.iter_mut() {
+            if let FnArg::Typed(pat_type) = arg {
+                if let Pat::Ident(pat_ident) = pat_type.pat.as_mut() {
+                    pat_ident.mutability = None;
+                }
+            }
+        }
+        let owner = match sig.inputs.first() {
+            Some(FnArg::Receiver(_)) => quote!(::std::any::type_name::<Self>()),
+            _ => quote!(::std::module_path!()),
+        };
+        // a function belongs to no component, so its interceptors are required by every app
+        let has_owner = match owner_ty {
+            Some(ty) => quote!(::springboot::interceptor::Interception::has_component::<#ty>),
+            None => quote!(|_| true),
+        };
+
+        let mut body = quote!(async move #block);
+        for (i, interceptor) in interceptors.iter().rev().enumerate() {
+            // only the method body needs the `mut` of the arguments
+            let pats = if i == 0 {
+                quote!(#(#arg_pats,)*)
+            } else {
+                quote!(#(#arg_names,)*)
+            };
+            body = quote! {
+                async move {
+                    ::springboot::interceptor::submit! {
+                        ::springboot::interceptor::Interception {
+                            method: ::std::concat!(::std::module_path!(), "::", ::std::stringify!(#name)),
+                            interceptor: ::std::any::type_name::<#interceptor>,
+                            has_owner: #has_owner,
+                            has_interceptor: ::springboot::interceptor::Interception::has_component::<#interceptor>,
+                        }
+                    }
+                    let __invocation = ::springboot::interceptor::Invocation {
+                        owner: #owner,
+                        method: ::std::stringify!(#name),
+                    };
+                    ::springboot::interceptor::intercept::<#interceptor, _, _, _, _, _>(
+                        __invocation,
+                        &[#(::std::stringify!(#arg_names)),*],
+                        (#(#arg_names,)*),
+                        move |(#pats)| #body,
+                    )
+                    .await
+                }
+            };
+        }
+
+        output.extend(quote! {
+            #(#attrs)*
+            #vis #defaultness #sig {
+                #body.await
+            }
+        });
+    }
+}
+
+pub(crate) fn with_around(args: TokenStream, ast: ImplItemFn) -> syn::Result<TokenStream> {
+    let args = syn::parse2::<AroundArgs>(args)?;
+    Ok(Around::new(args, ast)?.into_token_stream())
+}
+
+/// `#[around] impl` names the owner of the intercepted methods,
+/// their interceptors are only required by the apps with the owner component
+pub(crate) fn with_around_impl(args: TokenStream, mut item: syn::ItemImpl) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        return Err(syn::Error::new_spanned(
+            args,
+            "the interceptors belong to the methods, expected: #[around] impl",
+        ));
+    }
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&item.generics, "intercepted impl can not be generic"));
+    }
+    let owner = &item.self_ty;
+    for impl_item in &mut item.items {
+        if let syn::ImplItem::Fn(method) = impl_item {
+            for attr in &mut method.attrs {
+                if attr.path().segments.last().is_some_and(|segment| segment.ident == "around") {
+                    let interceptor = attr.parse_args::<AroundArgs>()?.interceptor;
+                    let path = attr.path().clone();
+                    *attr = syn::parse_quote!(#[#path(#interceptor, owner = #owner)]);
+                }
+            }
+        }
+    }
+    Ok(item.into_token_stream())
+}
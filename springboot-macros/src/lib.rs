@@ -1,8 +1,19 @@
+mod async_method;
 mod auto;
+mod cache;
+mod command;
 mod config;
+mod controller;
+mod exception;
 mod inject;
+mod interceptor;
+mod resilience;
+mod retry;
 mod route;
+mod runner;
 mod scope;
+mod starter;
+mod util;
 
 use proc_macro::TokenStream;
 use syn::DeriveInput;
@@ -25,6 +36,8 @@ fn input_and_compile_error(mut item: TokenStream, err: syn::Error) -> TokenStrea
 /// - `"path"`: Raw literal string with path for which to register handler.
 /// - `method = "HTTP_METHOD"`: Registers HTTP method to provide guard for. Upper-case string,
 ///   "GET", "POST" for example.
+/// - `layer = expr`: A tower layer applied to this handler only, may be repeated, the last one is the outermost.
+/// - `timeout = "2s"`: Answers 408 when the handler takes longer, in `ms`, `s`, `m` or `h`.
 ///
 /// # Examples
 /// ```
@@ -73,6 +86,22 @@ pub fn routes(_: TokenStream, input: TokenStream) -> TokenStream {
     route::with_methods(input)
 }
 
+/// Sends the response of a handler as is when the `[web.envelope]` is enabled.
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::{get, raw_response};
+/// #[get("/health")]
+/// #[raw_response]
+/// async fn health() -> &'static str {
+///     "UP"
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn raw_response(args: TokenStream, input: TokenStream) -> TokenStream {
+    route::with_raw_response(args, input)
+}
+
 macro_rules! method_macro {
     ($variant:ident, $method:ident) => {
         ///
@@ -83,10 +112,12 @@ macro_rules! method_macro {
         ///
         /// # Attributes
         /// - `"path"`: Raw literal string with path for which to register handler.
+        /// - `layer = expr`: A tower layer applied to this handler only, may be repeated.
+        /// - `timeout = "2s"`: Answers 408 when the handler takes longer.
         ///
         /// # Examples
         /// ```
-        /// # use spring_web::axum::response::IntoResponse;
+        /// # use springboot_web::axum::response::IntoResponse;
         #[doc = concat!("# use springboot_macros::", stringify!($method), ";")]
         #[doc = concat!("#[", stringify!($method), r#"("/")]"#)]
         /// async fn example() -> impl IntoResponse {
@@ -115,8 +146,8 @@ method_macro!(Patch, patch);
 /// # Syntax
 ///
 /// ```
-/// # use spring_macros::nest;
-/// #[nest("/prefix")]
+/// # use springboot_macros::scope;
+/// #[scope("/prefix")]
 /// mod api {
 ///     // ...
 /// }
@@ -125,12 +156,47 @@ method_macro!(Patch, patch);
 /// # Arguments
 ///
 /// - `"/prefix"` - Raw literal string to be prefixed onto contained handlers' paths.
+/// - `layer = expr`, `timeout = "2s"` - Applied once to the routes of the scope with `Router::route_layer`,
+///   around the handler's own layers. The durations must be positive.
+/// - `fallback = handler` - Answers the requests under the prefix that match no handler,
+///   and the prefix itself unless a handler has that path.
+///
+/// Nested modules are scanned too, a nested `#[scope]` is prefixed by the outer one and inherits its layers.
+/// Two handlers of the same method and path within a scope are a compile error,
+/// the paths are compared without their parameter names, so `/post/{id}` and `/post/{pid}` collide:
+///
+/// ```compile_fail
+/// # use springboot_macros::{scope, get};
+/// #[scope("/api")]
+/// mod api {
+///     # use super::*;
+///     #[get("/v1/users")]
+///     pub async fn users() -> &'static str {
+///         "users"
+///     }
+///
+///     #[scope("/v1")]
+///     pub mod v1 {
+///         # use super::*;
+///         // also GET /api/v1/users
+///         #[get("/users")]
+///         pub async fn list() -> &'static str {
+///             "list"
+///         }
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// A scope only sees its own module: the duplicates across top level scopes and controllers,
+/// including their fallbacks, are not a compile error. They are only found when the app starts,
+/// see `springboot_web::handler::check_routes`.
 ///
 /// # Example
 ///
 /// ```
-/// # use spring_macros::{nest, get};
-/// # use spring_web::axum::response::IntoResponse;
+/// # use springboot_macros::{scope, get};
+/// # use springboot_web::axum::response::IntoResponse;
 /// #[scope("/api")]
 /// mod api {
 ///     # use super::*;
@@ -142,11 +208,103 @@ method_macro!(Patch, patch);
 /// }
 /// # fn main() {}
 /// ```
+///
+/// Middleware of a module and of a single handler:
+///
+/// ```
+/// # use springboot_macros::{scope, get};
+/// use springboot_web::axum::extract::Request;
+/// use springboot_web::axum::http::StatusCode;
+/// use springboot_web::axum::middleware::{from_fn, Next};
+/// use springboot_web::axum::response::Response;
+///
+/// async fn require_admin(req: Request, next: Next) -> Result<Response, StatusCode> {
+///     match req.headers().get("x-role") {
+///         Some(role) if role == "admin" => Ok(next.run(req).await),
+///         _ => Err(StatusCode::FORBIDDEN),
+///     }
+/// }
+///
+/// #[scope("/admin", layer = from_fn(require_admin))]
+/// mod admin {
+///     # use super::*;
+///     #[get("/report", timeout = "2s")]
+///     pub async fn report() -> &'static str {
+///         "report"
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Nested scopes with a fallback:
+///
+/// ```
+/// # use springboot_macros::{scope, get};
+/// # use springboot_web::axum::http::StatusCode;
+/// #[scope("/api", fallback = not_found)]
+/// mod api {
+///     # use super::*;
+///     pub async fn not_found() -> (StatusCode, &'static str) {
+///         (StatusCode::NOT_FOUND, "no such api")
+///     }
+///
+///     #[scope("/v1")]
+///     pub mod v1 {
+///         # use super::*;
+///         #[get("/users")]
+///         pub async fn users() -> &'static str {
+///             // this has path /api/v1/users
+///             "users"
+///         }
+///     }
+/// }
+/// # fn main() {}
+/// ```
 #[proc_macro_attribute]
 pub fn scope(args: TokenStream, input: TokenStream) -> TokenStream {
     scope::with_scope(args, input)
 }
 
+/// Groups handlers as methods of a struct built by dependency injection.
+///
+/// `#[controller("/prefix")]` on a struct installs it as a [Service], its fields are injected
+/// with `#[inject(..)]`. `#[controller]` on its impl block registers the async methods taking
+/// `&self` and annotated with route macros, their paths are prefixed by the controller.
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::{controller, get};
+/// use springboot_web::axum::extract::Path;
+///
+/// #[derive(Clone)]
+/// struct Greeting(&'static str);
+///
+/// #[controller("/hello")]
+/// struct HelloController {
+///     #[inject(component)]
+///     greeting: Greeting,
+/// }
+///
+/// #[controller]
+/// impl HelloController {
+///     /// this has path /hello/{name}
+///     #[get("/{name}")]
+///     async fn hello(&self, Path(name): Path<String>) -> String {
+///         format!("{} {name}", self.greeting.0)
+///     }
+///
+///     fn unused(&self) {}
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_attribute]
+pub fn controller(args: TokenStream, input: TokenStream) -> TokenStream {
+    match controller::with_controller(args.into(), input.clone().into()) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
 /// Auto config
 /// ```diff
 ///  use spring_macros::auto_config;
@@ -171,6 +329,481 @@ pub fn auto_config(args: TokenStream, input: TokenStream) -> TokenStream {
     auto::config(args, input)
 }
 
+/// Registers a one-off command that runs with the fully built application.
+///
+/// When the first non-option argument of the process, or its `--command=<name>` option,
+/// matches the command name, `App::run` executes the command instead of the schedulers and exits with its status.
+/// Any other name exits with an error listing the known commands.
+///
+/// # Syntax
+/// ```plain
+/// #[command("name")]
+/// ```
+///
+/// # Arguments
+/// Every argument is resolved through `springboot::runner::FromApp`, such as
+/// `Arc<App>`, `ApplicationArguments`, `ComponentRef<T>` and `ConfigRef<T>`.
+/// The return value is converted through `springboot::runner::ExitStatus`.
+///
+/// # Examples
+/// ```
+/// # use springboot::runner::ApplicationArguments;
+/// # use springboot_macros::command;
+/// // ./app reindex --batch=100
+/// #[command("reindex")]
+/// async fn reindex(args: ApplicationArguments) -> springboot::error::Result<()> {
+///     let _batch = args.get_option_values("batch");
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn command(args: TokenStream, input: TokenStream) -> TokenStream {
+    command::with_command(args, input)
+}
+
+/// Registers a component as a runner executed once the application is ready.
+///
+/// The struct must be a component of the app, usually a `Service`, and implement
+/// `springboot::runner::ApplicationRunner`, or `springboot::runner::CommandLineRunner`
+/// with `#[runner(command_line)]`. An app without the component doesn't run it.
+///
+/// # Examples
+/// ```
+/// # use springboot::application::App;
+/// # use springboot::async_trait;
+/// # use springboot::error::Result;
+/// # use springboot::runner::{ApplicationArguments, ApplicationRunner};
+/// # use springboot_macros::{runner, Service};
+/// # use std::sync::Arc;
+/// #[derive(Clone, Service)]
+/// #[runner]
+/// struct WarmUp;
+///
+/// #[async_trait]
+/// impl ApplicationRunner for WarmUp {
+///     async fn run(&self, _app: Arc<App>, _args: &ApplicationArguments) -> Result<()> {
+///         Ok(())
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn runner(args: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = match syn::parse::<syn::ItemStruct>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match runner::with_runner(args.into(), ast) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
+/// Registers a starter that is added to every app automatically, like Spring Boot auto-configuration.
+///
+/// The struct must be a unit struct or implement `Default`, and implement `springboot::component::Starter`.
+/// A starter of the same type added with `AppBuilder::add_starter` wins over the discovered one,
+/// and a discovered starter is skipped when its type name is listed in `spring.autoconfigure.exclude`.
+/// `#[starter(logger)]` installs the starter as the logger, unless the app has set one.
+///
+/// # Examples
+/// ```
+/// # use springboot::application::AppBuilder;
+/// # use springboot::component::Starter;
+/// # use springboot_macros::starter;
+/// #[starter]
+/// struct CacheStarter;
+///
+/// impl Starter for CacheStarter {
+///     fn build(&self, _app: &mut AppBuilder) {}
+/// }
+/// ```
+///
+/// ```toml
+/// [spring.autoconfigure]
+/// exclude = ["CacheStarter"]
+/// ```
+#[proc_macro_attribute]
+pub fn starter(args: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = match syn::parse::<syn::ItemStruct>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match starter::with_starter(args.into(), ast) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
+/// Runs a service method on a pool of the `TaskExecutor` instead of the caller's task.
+///
+/// The method must be an `async fn` taking `&self` and owned arguments, and returning `()` or a `Result`.
+/// The service is cloned into the task, and the method returns a `springboot::executor::AsyncResult`
+/// right away, which resolves to the return value. If it is dropped without being awaited,
+/// an error is passed to the handler set by `AppBuilder::uncaught_error_handler`.
+///
+/// # Syntax
+/// ```plain
+/// #[async_method]
+/// #[async_method(pool = "name")]
+/// ```
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::async_method;
+/// #[derive(Clone)]
+/// struct MailService;
+///
+/// impl MailService {
+///     #[async_method(pool = "mail")]
+///     async fn send_mail(&self, to: String) -> springboot::error::Result<()> {
+///         let _ = to;
+///         Ok(())
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn async_method(args: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = match syn::parse::<syn::ImplItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match async_method::with_async_method(args.into(), ast) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
+/// Caches the result of an async function in the `CacheManager` component.
+///
+/// The cached value is returned without calling the function if the key is present.
+/// If the function returns a `Result`, only `Ok` values are cached.
+/// The key is the path of the function followed by the `Debug` of the arguments, unless an expression
+/// is given whose `to_string()` is used. Set the same `key` on [macro@cache_put] and [macro@cache_evict]
+/// to update the entries, the default keys of different functions never match.
+/// Values are stored as json, so they must implement `Serialize` and `DeserializeOwned`.
+///
+/// # Syntax
+/// ```plain
+/// #[cacheable("cache_name")]
+/// #[cacheable("cache_name", key = "expr")]
+/// ```
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::cacheable;
+/// #[derive(Clone)]
+/// struct UserService;
+///
+/// impl UserService {
+///     #[cacheable("users", key = "id")]
+///     async fn find_name(&self, id: i64) -> springboot::error::Result<String> {
+///         Ok(format!("user-{id}"))
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn cacheable(args: TokenStream, input: TokenStream) -> TokenStream {
+    with_cache(cache::CacheOp::Cacheable, args, input)
+}
+
+/// Always calls the async function and caches its result, see [macro@cacheable].
+///
+/// # Syntax
+/// ```plain
+/// #[cache_put("cache_name")]
+/// #[cache_put("cache_name", key = "expr")]
+/// ```
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::cache_put;
+/// #[cache_put("users", key = "id")]
+/// async fn rename(id: i64, name: String) -> springboot::error::Result<String> {
+///     Ok(name)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn cache_put(args: TokenStream, input: TokenStream) -> TokenStream {
+    with_cache(cache::CacheOp::Put, args, input)
+}
+
+/// Removes a key, or all keys, from a cache after the async function returned.
+/// Nothing is removed if the function returned an `Err`.
+///
+/// # Syntax
+/// ```plain
+/// #[cache_evict("cache_name")]
+/// #[cache_evict("cache_name", key = "expr")]
+/// #[cache_evict("cache_name", all_entries = true)]
+/// ```
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::cache_evict;
+/// #[cache_evict("users", key = "id")]
+/// async fn delete(id: i64) -> springboot::error::Result<()> {
+///     Ok(())
+/// }
+///
+/// #[cache_evict("users", all_entries = true)]
+/// async fn delete_all() {}
+/// ```
+#[proc_macro_attribute]
+pub fn cache_evict(args: TokenStream, input: TokenStream) -> TokenStream {
+    with_cache(cache::CacheOp::Evict, args, input)
+}
+
+fn with_cache(op: cache::CacheOp, args: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = match syn::parse::<syn::ImplItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match cache::with_cache(op, args.into(), ast) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
+/// Retries an async function returning a `Result` with exponential backoff.
+///
+/// The arguments are cloned for each attempt, so they must implement `Clone`.
+/// Attempts are logged, and the last error is returned, or passed to the `recover` function
+/// together with the arguments.
+///
+/// # Syntax
+/// ```plain
+/// #[retryable]
+/// #[retryable(max_attempts = 3, delay = 100, multiplier = 2.0, max_delay = 10000, jitter = true)]
+/// #[retryable(retry_on = "predicate", recover = "fallback")]
+/// ```
+///
+/// # Attributes
+/// - `max_attempts`: Attempts including the first call, 3 by default.
+/// - `delay`: Milliseconds before the second attempt, 100 by default.
+/// - `multiplier`: Factor the delay grows by, 2 by default.
+/// - `max_delay`: Upper bound of the delay in milliseconds, 10000 by default.
+/// - `jitter`: Randomize the delay between half and the full value, true by default.
+/// - `retry_on`: Path of a `fn(&E) -> bool` deciding whether the error is retried, all errors by default.
+/// - `recover`: Path of an async fn annotated with [macro@recover], a method if it is a plain name and the function takes `self`.
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::{recover, retryable};
+/// fn is_transient(e: &std::io::Error) -> bool {
+///     e.kind() == std::io::ErrorKind::TimedOut
+/// }
+///
+/// #[derive(Clone)]
+/// struct PriceService;
+///
+/// impl PriceService {
+///     #[retryable(max_attempts = 5, delay = 50, retry_on = "is_transient", recover = "cached_price")]
+///     async fn price(&self, sku: String) -> std::io::Result<u64> {
+///         Err(std::io::ErrorKind::TimedOut.into())
+///     }
+///
+///     #[recover]
+///     async fn cached_price(&self, e: std::io::Error, sku: String) -> std::io::Result<u64> {
+///         Ok(0)
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn retryable(args: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = match syn::parse::<syn::ImplItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match retry::with_retryable(args.into(), ast) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
+/// Marks the fallback of a [macro@retryable] function.
+///
+/// It takes the last error followed by the arguments of the retryable function,
+/// and returns the same type.
+#[proc_macro_attribute]
+pub fn recover(args: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = match syn::parse::<syn::ImplItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match retry::with_recover(args.into(), ast) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
+/// Runs an async function through the circuit breaker and the bulkhead with the given name.
+///
+/// They are configured under `[resilience.<name>]`. The function must return a `Result` whose error
+/// implements `From<springboot::error::AppError>`, rejected calls return
+/// `AppError::CallNotPermitted` or `AppError::BulkheadFull`.
+///
+/// # Syntax
+/// ```plain
+/// #[circuit_breaker("name", record_on = "predicate")]
+/// ```
+///
+/// # Attributes
+/// - `record_on`: Path of a `fn(&E) -> bool` deciding whether the error is recorded as a failure,
+///   all errors by default. The other errors are not recorded at all, e.g. the errors of invalid arguments.
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::circuit_breaker;
+/// use springboot::error::AppError;
+///
+/// // only the failures of the disk open the breaker
+/// fn is_io(e: &AppError) -> bool {
+///     matches!(e, AppError::IOError(_))
+/// }
+///
+/// #[derive(Clone)]
+/// struct StockService;
+///
+/// impl StockService {
+///     #[circuit_breaker("inventory")]
+///     async fn stock(&self, sku: String) -> springboot::error::Result<u64> {
+///         Ok(sku.len() as u64)
+///     }
+///
+///     #[circuit_breaker("inventory", record_on = "is_io")]
+///     async fn snapshot(&self, path: String) -> springboot::error::Result<String> {
+///         Ok(std::fs::read_to_string(path)?)
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn circuit_breaker(args: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = match syn::parse::<syn::ImplItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match resilience::with_circuit_breaker(args.into(), ast) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
+/// Wraps an async function with an interceptor, see `springboot::interceptor::Interceptor`.
+///
+/// The interceptor is looked up as a component of the current app, an app without it fails to start.
+/// It receives the method name and the rest of the call, which lends the arguments as `Debug` until it proceeds.
+/// The function must return a `Result` whose error implements `From<springboot::error::AppError>`
+/// and its arguments must implement `Debug`. Several interceptors can be stacked, the first one is the outermost.
+///
+/// The impl block of intercepted methods is annotated with `#[around]` too, it names the owner component:
+/// only the apps with the owner need its interceptors. The interceptors of functions are required by every app.
+///
+/// # Syntax
+/// ```plain
+/// #[around] impl OwnerType { #[around(InterceptorType)] async fn method(&self) ... }
+/// #[around(InterceptorType)]
+/// ```
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::around;
+/// use springboot::error::Result;
+/// use springboot::interceptor::{Interceptor, Invocation, MethodResult, Proceed};
+///
+/// #[derive(Clone)]
+/// struct Timing;
+///
+/// impl Interceptor for Timing {
+///     async fn around<R: MethodResult>(&self, invocation: &Invocation, proceed: Proceed<'_, R>) -> Result<R> {
+///         let start = std::time::Instant::now();
+///         let result = proceed.proceed().await;
+///         println!("{} took {:?}", invocation.method, start.elapsed());
+///         Ok(result)
+///     }
+/// }
+///
+/// #[around(Timing)]
+/// async fn find_user(id: i64) -> Result<String> {
+///     Ok(format!("user-{id}"))
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn around(args: TokenStream, input: TokenStream) -> TokenStream {
+    if let Ok(item) = syn::parse::<syn::ItemImpl>(input.clone()) {
+        return match interceptor::with_around_impl(args.into(), item) {
+            Ok(stream) => stream.into(),
+            Err(err) => input_and_compile_error(input, err),
+        };
+    }
+    let ast = match syn::parse::<syn::ImplItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match interceptor::with_around(args.into(), ast) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
+/// Maps an error type to a response for all the web handlers.
+///
+/// A handler returning `springboot_web::error::Result` that fails with an error convertible into
+/// `anyhow::Error` is answered by the exception handler of the error type, before the default
+/// 500 problem details. The function takes a reference to the error and returns `impl IntoResponse`.
+///
+/// # Syntax
+/// ```plain
+/// #[exception_handler(ErrorType)]
+/// ```
+///
+/// # Examples
+/// ```
+/// # use springboot_macros::exception_handler;
+/// use springboot_web::axum::http::StatusCode;
+/// use springboot_web::axum::response::IntoResponse;
+/// use springboot_web::problem::Problem;
+///
+/// #[derive(Debug)]
+/// struct PostNotFound(u64);
+///
+/// impl std::fmt::Display for PostNotFound {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "post {} not found", self.0)
+///     }
+/// }
+///
+/// impl std::error::Error for PostNotFound {}
+///
+/// #[exception_handler(PostNotFound)]
+/// fn post_not_found(e: &PostNotFound) -> impl IntoResponse {
+///     Problem::new(StatusCode::NOT_FOUND, Some(e.to_string()))
+/// }
+///
+/// let error = springboot_web::anyhow::Error::new(PostNotFound(7));
+/// let response = springboot_web::exception::handle_exception(&error).unwrap();
+/// assert_eq!(response.status(), StatusCode::NOT_FOUND);
+/// ```
+#[proc_macro_attribute]
+pub fn exception_handler(args: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = match syn::parse::<syn::ItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match exception::with_exception_handler(args.into(), ast) {
+        Ok(stream) => stream.into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
+
 /// Configurable
 #[proc_macro_derive(Configurable, attributes(config_prefix))]
 pub fn derive_config(input: TokenStream) -> TokenStream {
@@ -0,0 +1,86 @@
+use crate::util::returns_result;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{ImplItemFn, LitStr, Token};
+
+/// `"name", record_on = "path"`
+struct CircuitBreakerArgs {
+    name: LitStr,
+    record_on: Option<syn::Path>,
+}
+
+impl Parse for CircuitBreakerArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse::<LitStr>()?;
+        let mut record_on = None;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let option = input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            match option.to_string().as_str() {
+                "record_on" => record_on = Some(input.parse::<LitStr>()?.parse()?),
+                _ => return Err(syn::Error::new_spanned(option, "unknown attribute, expected record_on")),
+            }
+        }
+        Ok(Self { name, record_on })
+    }
+}
+
+struct CircuitBreaker {
+    name: LitStr,
+    record_on: Option<syn::Path>,
+
+    /// AST of the function being annotated.
+    ast: ImplItemFn,
+}
+
+impl CircuitBreaker {
+    fn new(CircuitBreakerArgs { name, record_on }: CircuitBreakerArgs, ast: ImplItemFn) -> syn::Result<Self> {
+        if ast.sig.asyncness.is_none() {
+            return Err(syn::Error::new_spanned(
+                ast.sig.fn_token,
+                "only support async fn as circuit breaker",
+            ));
+        }
+        if !returns_result(&ast.sig.output) {
+            return Err(syn::Error::new_spanned(
+                &ast.sig,
+                "circuit breaker fn must return a `Result` whose error implements `From<springboot::error::AppError>`",
+            ));
+        }
+        Ok(Self { name, record_on, ast })
+    }
+}
+
+impl ToTokens for CircuitBreaker {
+    fn to_tokens(&self, output: &mut TokenStream) {
+        let Self { name, record_on, ast } = self;
+        let ImplItemFn {
+            attrs,
+            vis,
+            defaultness,
+            sig,
+            block,
+        } = ast;
+        let record_on = match record_on {
+            Some(record_on) => quote!(#record_on),
+            None => quote!(|_| true),
+        };
+
+        output.extend(quote! {
+            #(#attrs)*
+            #vis #defaultness #sig {
+                ::springboot::resilience::guarded(#name, #record_on, async move #block).await
+            }
+        });
+    }
+}
+
+pub(crate) fn with_circuit_breaker(args: TokenStream, ast: ImplItemFn) -> syn::Result<TokenStream> {
+    let args = syn::parse2::<CircuitBreakerArgs>(args)?;
+    Ok(CircuitBreaker::new(args, ast)?.into_token_stream())
+}
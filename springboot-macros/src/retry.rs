@@ -0,0 +1,204 @@
+use crate::util::returns_result;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{FnArg, ImplItemFn, Lit, LitStr, Pat, Token};
+
+/// `max_attempts = 3, delay = 100, multiplier = 2.0, max_delay = 10000, jitter = true, retry_on = "path", recover = "path"`
+#[derive(Default)]
+struct RetryArgs {
+    max_attempts: Option<syn::LitInt>,
+    delay: Option<syn::LitInt>,
+    multiplier: Option<Lit>,
+    max_delay: Option<syn::LitInt>,
+    jitter: Option<syn::LitBool>,
+    retry_on: Option<syn::Path>,
+    recover: Option<syn::Path>,
+}
+
+impl Parse for RetryArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        while !input.is_empty() {
+            let name = input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            match name.to_string().as_str() {
+                "max_attempts" => args.max_attempts = Some(input.parse()?),
+                "delay" => args.delay = Some(input.parse()?),
+                "multiplier" => args.multiplier = Some(input.parse()?),
+                "max_delay" => args.max_delay = Some(input.parse()?),
+                "jitter" => args.jitter = Some(input.parse()?),
+                "retry_on" => args.retry_on = Some(input.parse::<LitStr>()?.parse()?),
+                "recover" => args.recover = Some(input.parse::<LitStr>()?.parse()?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        "unknown attribute, expected one of max_attempts, delay, multiplier, max_delay, jitter, retry_on, recover",
+                    ))
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl RetryArgs {
+    fn backoff(&self) -> TokenStream {
+        let mut fields = vec![];
+        if let Some(max_attempts) = &self.max_attempts {
+            fields.push(quote!(max_attempts: #max_attempts));
+        }
+        if let Some(delay) = &self.delay {
+            fields.push(quote!(delay: ::std::time::Duration::from_millis(#delay)));
+        }
+        if let Some(multiplier) = &self.multiplier {
+            fields.push(quote!(multiplier: #multiplier as f64));
+        }
+        if let Some(max_delay) = &self.max_delay {
+            fields.push(quote!(max_delay: ::std::time::Duration::from_millis(#max_delay)));
+        }
+        if let Some(jitter) = &self.jitter {
+            fields.push(quote!(jitter: #jitter));
+        }
+        quote! {
+            ::springboot::retry::Backoff {
+                #(#fields,)*
+                ..::std::default::Default::default()
+            }
+        }
+    }
+}
+
+fn has_receiver(ast: &ImplItemFn) -> bool {
+    matches!(ast.sig.inputs.first(), Some(FnArg::Receiver(_)))
+}
+
+struct Retryable {
+    args: RetryArgs,
+    arg_names: Vec<syn::Ident>,
+
+    /// AST of the function being annotated.
+    ast: ImplItemFn,
+}
+
+impl Retryable {
+    fn new(args: RetryArgs, ast: ImplItemFn) -> syn::Result<Self> {
+        if ast.sig.asyncness.is_none() {
+            return Err(syn::Error::new_spanned(
+                ast.sig.fn_token,
+                "only support async fn as retryable",
+            ));
+        }
+        if !returns_result(&ast.sig.output) {
+            return Err(syn::Error::new_spanned(
+                &ast.sig,
+                "retryable fn must return a `Result`",
+            ));
+        }
+        let mut arg_names = vec![];
+        for arg in &ast.sig.inputs {
+            if let FnArg::Typed(pat_type) = arg {
+                match pat_type.pat.as_ref() {
+                    Pat::Ident(pat_ident) => arg_names.push(pat_ident.ident.clone()),
+                    pat => {
+                        return Err(syn::Error::new_spanned(
+                            pat,
+                            "retryable fn only supports plain arguments, they are cloned for each attempt",
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            args,
+            arg_names,
+            ast,
+        })
+    }
+}
+
+impl ToTokens for Retryable {
+    fn to_tokens(&self, output: &mut TokenStream) {
+        let Self {
+            args,
+            arg_names,
+            ast,
+        } = self;
+        let ImplItemFn {
+            attrs,
+            vis,
+            defaultness,
+            sig,
+            block,
+        } = ast;
+        let name = &sig.ident;
+        let backoff = args.backoff();
+        let retry_on = match &args.retry_on {
+            Some(retry_on) => quote!(#retry_on),
+            None => quote!(::springboot::retry::any_error),
+        };
+        let call = quote! {
+            ::springboot::retry::retry(
+                ::std::concat!(::std::module_path!(), "::", ::std::stringify!(#name)),
+                &#backoff,
+                #retry_on,
+                || {
+                    #(let #arg_names = ::std::clone::Clone::clone(&#arg_names);)*
+                    async move #block
+                },
+            )
+            .await
+        };
+        let body = match &args.recover {
+            Some(recover) => {
+                let recover = if has_receiver(ast) && recover.get_ident().is_some() {
+                    quote!(self.#recover)
+                } else {
+                    quote!(#recover)
+                };
+                quote! {
+                    match #call {
+                        ::std::result::Result::Ok(value) => ::std::result::Result::Ok(value),
+                        ::std::result::Result::Err(e) => #recover(e, #(#arg_names),*).await,
+                    }
+                }
+            }
+            None => call,
+        };
+
+        output.extend(quote! {
+            #(#attrs)*
+            #vis #defaultness #sig {
+                #body
+            }
+        });
+    }
+}
+
+pub(crate) fn with_retryable(args: TokenStream, ast: ImplItemFn) -> syn::Result<TokenStream> {
+    let args = syn::parse2::<RetryArgs>(args)?;
+    Ok(Retryable::new(args, ast)?.into_token_stream())
+}
+
+pub(crate) fn with_recover(args: TokenStream, ast: ImplItemFn) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        return Err(syn::Error::new_spanned(args, "recover takes no arguments"));
+    }
+    if ast.sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            ast.sig.fn_token,
+            "only support async fn as recover",
+        ));
+    }
+    let skip = usize::from(has_receiver(&ast));
+    if ast.sig.inputs.len() <= skip {
+        return Err(syn::Error::new_spanned(
+            &ast.sig,
+            "recover fn must take the error as its first argument, followed by the arguments of the retryable fn",
+        ));
+    }
+    Ok(ast.into_token_stream())
+}
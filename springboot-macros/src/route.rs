@@ -20,7 +20,7 @@ macro_rules! standard_http_method {
         impl MethodType {
 
             #[allow(dead_code)]
-            fn as_str(&self) -> &'static str {
+            pub(crate) fn as_str(&self) -> &'static str {
                 match self {
                     $(Self::$variant => stringify!($variant),)+
                 }
@@ -84,11 +84,71 @@ impl TryFrom<&syn::LitStr> for MethodType {
 struct Args {
     path: syn::LitStr,
     methods: HashSet<MethodType>,
+    /// `layer = ..` and `timeout = ".."` in the order of the attribute, the last one is the outermost
+    layers: Vec<TokenStream2>,
+    /// Registrar of the `#[scope]` registering the handler, set by the scope
+    scope: Option<syn::Path>,
+}
+
+/// Milliseconds of a duration such as `"500ms"`, `"2s"`, `"1m"` or `"1h"`
+pub(crate) fn parse_duration(lit: &syn::LitStr) -> syn::Result<u64> {
+    let value = lit.value();
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let factor = match unit.trim() {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        _ => 0,
+    };
+    let amount = match amount.parse::<u64>() {
+        Ok(amount) if factor > 0 && amount > 0 => amount,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                lit,
+                "invalid duration, expected a positive number followed by ms, s, m or h, such as \"2s\"",
+            ))
+        }
+    };
+    amount
+        .checked_mul(factor)
+        .ok_or_else(|| syn::Error::new_spanned(lit, "duration is too long"))
+}
+
+/// The layer of a `layer = ..` or `timeout = ".."` option, `None` for other keys
+pub(crate) fn parse_layer(nv: &syn::MetaNameValue) -> syn::Result<Option<TokenStream2>> {
+    if nv.path.is_ident("layer") {
+        let layer = &nv.value;
+        Ok(Some(quote!(#layer)))
+    } else if nv.path.is_ident("timeout") {
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) = &nv.value
+        else {
+            return Err(syn::Error::new_spanned(
+                &nv.value,
+                "Attribute timeout expects literal string, such as \"2s\"",
+            ));
+        };
+        let millis = parse_duration(lit)?;
+        Ok(Some(quote! {
+            ::springboot_web::middleware::timeout::TimeoutLayer::new(
+                ::std::time::Duration::from_millis(#millis),
+            )
+        }))
+    } else {
+        Ok(None)
+    }
 }
 
 impl Args {
     fn new(args: RouteArgs, method: Option<MethodType>) -> syn::Result<Self> {
         let mut methods = HashSet::new();
+        let mut layers = vec![];
+        let mut scope = None;
 
         let is_route_macro: bool = method.is_none();
         if let Some(method) = method {
@@ -119,10 +179,14 @@ impl Args {
                         "Attribute method expects literal string",
                     ));
                 }
+            } else if let Some(layer) = parse_layer(&nv)? {
+                layers.push(layer);
+            } else if let (true, syn::Expr::Path(path)) = (nv.path.is_ident("__scope"), &nv.value) {
+                scope = Some(path.path.clone());
             } else {
                 return Err(syn::Error::new_spanned(
                     nv.path,
-                    "Unknown attribute key is specified; allowed: method",
+                    "Unknown attribute key is specified; allowed: method, layer, timeout",
                 ));
             }
         }
@@ -130,6 +194,8 @@ impl Args {
         Ok(Args {
             path: args.path,
             methods,
+            layers,
+            scope,
         })
     }
 }
@@ -181,7 +247,7 @@ impl syn::parse::Parse for RouteArgs {
     }
 }
 
-struct Route {
+pub(crate) struct Route {
     /// Name of the handler function being annotated.
     name: syn::Ident,
 
@@ -195,10 +261,39 @@ struct Route {
 
     /// The doc comment attributes to copy to generated struct, if any.
     doc_attributes: Vec<syn::Attribute>,
+
+    /// Whether the handler is annotated with `#[raw_response]`.
+    raw: bool,
+
+    /// The controller if the handler is a method of a `#[controller]` impl block.
+    controller: Option<ControllerMethod>,
+}
+
+/// A handler method of a controller, registered through a function resolving the controller
+struct ControllerMethod {
+    self_ty: syn::Type,
+    /// Name of the generated registrar, the controller and the method name
+    registrar: syn::Ident,
+    /// Signature of the method, for its OpenAPI operation
+    sig: syn::Signature,
+    /// Paths of the removed route attributes, still used by the generated code
+    attr_paths: Vec<syn::Path>,
+}
+
+fn is_raw_response(attr: &syn::Attribute) -> bool {
+    attr.path().segments.last().is_some_and(|segment| segment.ident == "raw_response")
+}
+
+/// Remove the `#[raw_response]` attribute of the handler, it is applied by the route macro
+fn take_raw_response(ast: &mut syn::ItemFn) -> bool {
+    let len = ast.attrs.len();
+    ast.attrs.retain(|attr| !is_raw_response(attr));
+    ast.attrs.len() != len
 }
 
 impl Route {
-    pub fn new(args: RouteArgs, ast: syn::ItemFn, method: Option<MethodType>) -> syn::Result<Self> {
+    pub fn new(args: RouteArgs, mut ast: syn::ItemFn, method: Option<MethodType>) -> syn::Result<Self> {
+        let raw = take_raw_response(&mut ast);
         let name = ast.sig.ident.clone();
 
         // Try and pull out the doc comments so that we can reapply them to the generated struct.
@@ -231,10 +326,13 @@ impl Route {
             args: vec![args],
             ast,
             doc_attributes,
+            raw,
+            controller: None,
         })
     }
 
-    fn multiple(args: Vec<Args>, ast: syn::ItemFn) -> syn::Result<Self> {
+    fn multiple(args: Vec<Args>, mut ast: syn::ItemFn) -> syn::Result<Self> {
+        let raw = take_raw_response(&mut ast);
         let name = ast.sig.ident.clone();
 
         // Try and pull out the doc comments so that we can reapply them to the generated struct.
@@ -258,10 +356,117 @@ impl Route {
             args,
             ast,
             doc_attributes,
+            raw,
+            controller: None,
         })
     }
 }
 
+impl Route {
+    /// The route of a method of a `#[controller]` impl block, `None` if it has no route attribute.
+    /// The route attributes are removed from the method.
+    pub(crate) fn controller(self_ty: &syn::Type, method: &mut syn::ImplItemFn) -> syn::Result<Option<Self>> {
+        let mut args = vec![];
+        let mut raw = false;
+        let mut attrs = vec![];
+        let mut attr_paths = vec![];
+        for attr in method.attrs.drain(..) {
+            let last = attr.path().segments.last().map(|segment| segment.ident.to_string());
+            match (MethodType::from_path(attr.path()), last.as_deref()) {
+                (Ok(method), _) => args.push(Args::new(attr.parse_args()?, Some(method))?),
+                (_, Some("route")) => args.push(Args::new(attr.parse_args()?, None)?),
+                (_, Some("routes")) => {}
+                (_, Some("raw_response")) => raw = true,
+                _ => {
+                    attrs.push(attr);
+                    continue;
+                }
+            }
+            attr_paths.push(attr.path().clone());
+        }
+        method.attrs = attrs;
+        if args.is_empty() {
+            return Ok(None);
+        }
+        if let Some(args) = args.iter().find(|args| args.methods.is_empty()) {
+            return Err(syn::Error::new_spanned(
+                &args.path,
+                "The #[route(..)] macro requires at least one `method` attribute",
+            ));
+        }
+
+        let sig = &method.sig;
+        if sig.asyncness.is_none() {
+            return Err(syn::Error::new_spanned(sig.fn_token, "only support async fn as controller handler"));
+        }
+        if !sig.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(&sig.generics, "controller handler can not be generic"));
+        }
+        match sig.inputs.first() {
+            Some(syn::FnArg::Receiver(receiver)) if receiver.reference.is_some() && receiver.mutability.is_none() => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    sig,
+                    "controller handler must take `&self`, the controller is resolved for each request",
+                ))
+            }
+        }
+        if matches!(sig.output, syn::ReturnType::Default) {
+            return Err(syn::Error::new_spanned(
+                sig,
+                "Function has no return type. Cannot be used as handler",
+            ));
+        }
+
+        let name = sig.ident.clone();
+        let (arg_names, arg_types): (Vec<syn::Ident>, Vec<&syn::Type>) = sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::FnArg::Typed(pat_type) => Some(pat_type.ty.as_ref()),
+                syn::FnArg::Receiver(_) => None,
+            })
+            .enumerate()
+            .map(|(i, ty)| (quote::format_ident!("__arg{}", i), ty))
+            .unzip();
+        let doc_attributes: Vec<syn::Attribute> = method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .cloned()
+            .collect();
+        let ast: syn::ItemFn = syn::parse_quote! {
+            async fn #name(#(#arg_names: #arg_types),*) -> ::springboot_web::axum::response::Response {
+                match ::springboot_web::controller::resolve::<#self_ty>() {
+                    ::std::result::Result::Ok(__controller) => ::springboot_web::axum::response::IntoResponse::into_response(
+                        __controller.#name(#(#arg_names),*).await,
+                    ),
+                    ::std::result::Result::Err(__error) => ::springboot_web::axum::response::IntoResponse::into_response(__error),
+                }
+            }
+        };
+        let type_name = match self_ty {
+            syn::Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()),
+            _ => None,
+        }
+        .ok_or_else(|| syn::Error::new_spanned(self_ty, "controller must be a named struct"))?;
+
+        Ok(Some(Self {
+            name,
+            args,
+            ast,
+            doc_attributes,
+            raw,
+            controller: Some(ControllerMethod {
+                self_ty: self_ty.clone(),
+                registrar: quote::format_ident!("__{}_{}", type_name, method.sig.ident),
+                sig: method.sig.clone(),
+                attr_paths,
+            }),
+        }))
+    }
+}
+
 impl ToTokens for Route {
     fn to_tokens(&self, output: &mut TokenStream2) {
         let Self {
@@ -269,15 +474,32 @@ impl ToTokens for Route {
             ast,
             args,
             doc_attributes,
+            raw,
+            controller,
         } = self;
 
         #[allow(unused_variables)] // used when force-pub feature is disabled
         let vis = &ast.vis;
 
+        // the paths of a controller are prefixed at runtime by the prefix of the controller struct
+        let route_path = |path: &LitStr| match controller {
+            Some(ControllerMethod { self_ty, .. }) => quote! {{
+                const __PATH_LEN: usize = ::springboot_web::controller::path_len::<#self_ty>(#path);
+                const __PATH_BYTES: [u8; __PATH_LEN] =
+                    ::springboot_web::controller::path_bytes::<#self_ty, __PATH_LEN>(#path);
+                const __PATH: &str = ::springboot_web::controller::path_str(&__PATH_BYTES);
+                __PATH
+            }},
+            None => quote!(#path),
+        };
+        let registrar = controller.as_ref().map_or(name, |c| &c.registrar);
+        let sig = controller.as_ref().map_or(&ast.sig, |c| &c.sig);
+
         let registrations: TokenStream2 = args
             .iter()
             .map(|args| {
-                let Args { path, methods } = args;
+                let Args { path, methods, layers, .. } = args;
+                let path = route_path(path);
                 let method_binder = methods
                     .iter()
                     .map(|m| quote! {
@@ -285,31 +507,208 @@ impl ToTokens for Route {
                         }
                     );
 
+                let raw_layer = raw.then(|| quote! {
+                    let __method_router = ::springboot_web::MethodRouter::layer(
+                        __method_router,
+                        ::springboot_web::axum::middleware::map_response(::springboot_web::envelope::mark_raw),
+                    );
+                });
+
                 quote! {
                     let __method_router = ::springboot_web::MethodRouter::new();
                     #(#method_binder)*
+                    #(
+                        let __method_router: ::springboot_web::MethodRouter<_, ::std::convert::Infallible> =
+                            ::springboot_web::MethodRouter::layer(__method_router, #layers);
+                    )*
+                    #raw_layer
                      __router = ::springboot_web::Router::route(__router, #path, __method_router);
                 }
             })
             .collect();
+        let docs: Vec<LitStr> = doc_attributes
+            .iter()
+            .filter_map(|attr| match &attr.meta {
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(doc), .. }),
+                    ..
+                }) => Some(doc.clone()),
+                _ => None,
+            })
+            .collect();
+        let schemas = OperationSchemas::new(sig);
+        let operations = args.iter().flat_map(|Args { path, methods, .. }| {
+            let path = route_path(path);
+            let mut methods: Vec<&str> = methods.iter().map(MethodType::as_str).collect();
+            methods.sort();
+            let docs = &docs;
+            methods.into_iter().map(move |method| {
+                let path = &path;
+                let method = method.to_lowercase();
+                quote! {
+                    ::springboot_web::openapi::Operation {
+                        path: #path,
+                        method: #method,
+                        operation_id: ::std::stringify!(#name),
+                        docs: &[#(#docs),*],
+                        schemas: __schemas,
+                    }
+                }
+            })
+        });
+        // the components injected by the extractors, and the controller resolved for each request
+        let extractors = ast.sig.inputs.iter().filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => match pat_type.ty.as_ref() {
+                syn::Type::ImplTrait(_) | syn::Type::Infer(_) => None,
+                ty => Some(ty),
+            },
+            syn::FnArg::Receiver(_) => None,
+        });
+        // the handlers of a scope are registered by the scope, which layers them once
+        let submit = match args.iter().find_map(|args| args.scope.as_ref()) {
+            Some(scope) => quote!(::springboot_web::submit_scoped_handler!(#registrar, #scope);),
+            None => quote!(::springboot_web::submit_typed_handler!(#registrar);),
+        };
+        let controller_dependency = controller.iter().map(|ControllerMethod { self_ty, .. }| {
+            quote!(::std::option::Option::Some(::springboot_web::extractor::Dependency::of::<#self_ty>()))
+        });
         let stream = quote! {
             #(#doc_attributes)*
             #[allow(non_camel_case_types, missing_docs)]
-            #vis struct #name;
+            #vis struct #registrar;
 
-            impl ::springboot_web::handler::HandlerRegistrar for #name {
+            impl ::springboot_web::handler::HandlerRegistrar for #registrar {
                 fn register(&self, mut __router: ::springboot_web::Router) -> ::springboot_web::Router{
                     #ast
                     #registrations
                     __router
 
                 }
+
+                fn dependencies(&self) -> ::std::vec::Vec<::springboot_web::extractor::Dependency> {
+                    #[allow(unused_imports)]
+                    use ::springboot_web::extractor::{InjectDependency as _, NoDependency as _};
+                    let __dependencies: ::std::vec::Vec<::std::option::Option<::springboot_web::extractor::Dependency>> = ::std::vec![
+                        #(#controller_dependency,)*
+                        #((&::springboot_web::extractor::DependencyProbe::<#extractors>::new()).dependency(),)*
+                    ];
+                    __dependencies.into_iter().flatten().collect()
+                }
+
+                fn operations(&self) -> ::std::vec::Vec<::springboot_web::openapi::Operation> {
+                    #[allow(clippy::needless_borrow)]
+                    fn __schemas(
+                        __gen: &mut ::springboot_web::openapi::schemars::gen::SchemaGenerator,
+                    ) -> ::springboot_web::openapi::OperationSchemas {
+                        #[allow(unused_imports)]
+                        use ::springboot_web::openapi::{WithSchema as _, WithoutSchema as _};
+                        #schemas
+                    }
+                    ::std::vec![#(#operations),*]
+                }
             }
 
-            ::springboot_web::submit_typed_handler!(#name);
+            #submit
         };
 
         output.extend(stream);
+
+        // the route attributes of a controller method are consumed by `#[controller]`,
+        // referencing them keeps their imports used
+        if let Some(ControllerMethod { attr_paths, .. }) = controller {
+            output.extend(quote! {
+                const _: () = {
+                    #(
+                        #[allow(unused_imports)]
+                        use #attr_paths as _;
+                    )*
+                };
+            });
+        }
+    }
+}
+
+/// Types of the extractors and the response of a handler, for its OpenAPI operation
+struct OperationSchemas {
+    path: Option<syn::Type>,
+    query: Option<syn::Type>,
+    body: Option<syn::Type>,
+    response: Option<syn::Type>,
+    validated: bool,
+}
+
+/// `T` of `Wrapper<T>` if the last segment of the type is one of `wrappers`
+fn unwrap_type<'a>(ty: &'a syn::Type, wrappers: &[&str]) -> Option<(String, &'a syn::Type)> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if !wrappers.iter().any(|wrapper| segment.ident == wrapper) {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        syn::GenericArgument::Type(inner) => Some((segment.ident.to_string(), inner)),
+        _ => None,
+    })
+}
+
+impl OperationSchemas {
+    fn new(sig: &syn::Signature) -> Self {
+        let mut schemas = Self {
+            path: None,
+            query: None,
+            body: None,
+            response: None,
+            validated: false,
+        };
+        for arg in &sig.inputs {
+            let syn::FnArg::Typed(pat_type) = arg else {
+                continue;
+            };
+            let valid = unwrap_type(&pat_type.ty, &["Valid"]);
+            schemas.validated |= valid.is_some();
+            let ty = valid.map_or(pat_type.ty.as_ref(), |(_, inner)| inner);
+            match unwrap_type(ty, &["Path", "Query", "Json"]) {
+                Some((wrapper, inner)) if wrapper == "Path" => schemas.path = Some(inner.clone()),
+                Some((wrapper, inner)) if wrapper == "Query" => schemas.query = Some(inner.clone()),
+                Some((wrapper, inner)) if wrapper == "Json" => schemas.body = Some(inner.clone()),
+                _ => {}
+            }
+        }
+        if let syn::ReturnType::Type(_, ty) = &sig.output {
+            let ty = unwrap_type(ty, &["Result"]).map_or(ty.as_ref(), |(_, ok)| ok);
+            schemas.response = unwrap_type(ty, &["Json"]).map(|(_, inner)| inner.clone());
+        }
+        schemas
+    }
+}
+
+impl ToTokens for OperationSchemas {
+    fn to_tokens(&self, output: &mut TokenStream2) {
+        let probe = |ty: &Option<syn::Type>, method: &str| {
+            let method = syn::Ident::new(method, Span::call_site());
+            match ty {
+                Some(ty) => quote!((&::springboot_web::openapi::SchemaProbe::<#ty>::new()).#method(__gen)),
+                None => quote!(::std::option::Option::None),
+            }
+        };
+        let path = probe(&self.path, "inline");
+        let query = probe(&self.query, "inline");
+        let body = probe(&self.body, "subschema");
+        let response = probe(&self.response, "subschema");
+        let validated = self.validated;
+        output.extend(quote! {
+            ::springboot_web::openapi::OperationSchemas {
+                path: #path,
+                query: #query,
+                body: #body,
+                response: #response,
+                validated: #validated,
+            }
+        });
     }
 }
 
@@ -337,6 +736,32 @@ pub(crate) fn with_method(
     }
 }
 
+/// `#[raw_response]` above the route attribute is moved below it, so the route macro sees it
+pub(crate) fn with_raw_response(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut ast = match syn::parse::<syn::ItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+    if !args.is_empty() {
+        let err = syn::Error::new(Span::call_site(), "raw_response takes no arguments");
+        return input_and_compile_error(input, err);
+    }
+    let is_route = |attr: &syn::Attribute| {
+        MethodType::from_path(attr.path()).is_ok()
+            || attr.path().segments.last().is_some_and(|s| s.ident == "route" || s.ident == "routes")
+    };
+    let Some(index) = ast.attrs.iter().position(is_route) else {
+        let err = syn::Error::new_spanned(
+            &ast.sig,
+            "#[raw_response] must be used with a route attribute such as #[get(\"/path\")]",
+        );
+        return input_and_compile_error(input, err);
+    };
+    ast.attrs.insert(index + 1, syn::parse_quote!(#[::springboot_web::raw_response]));
+    ast.into_token_stream().into()
+}
+
+#[allow(clippy::result_large_err)]
 pub(crate) fn with_methods(input: TokenStream) -> TokenStream {
     let mut ast = match syn::parse::<syn::ItemFn>(input.clone()) {
         Ok(ast) => ast,
@@ -382,3 +807,22 @@ pub(crate) fn with_methods(input: TokenStream) -> TokenStream {
         Err(err) => input_and_compile_error(input, err),
     }
 }
+
+#[allow(unused_imports)]
+mod tests {
+    use super::parse_duration;
+
+    #[test]
+    fn test_parse_duration() {
+        let parse = |value: &str| parse_duration(&syn::LitStr::new(value, proc_macro2::Span::call_site()));
+        assert_eq!(parse("500ms").unwrap(), 500);
+        assert_eq!(parse("2s").unwrap(), 2_000);
+        assert_eq!(parse(" 1 m ").unwrap(), 60_000);
+        assert_eq!(parse("1h").unwrap(), 3_600_000);
+        for invalid in ["", "2", "s", "-1s", "1.5s", "2d", "0s", "0ms"] {
+            assert!(parse(invalid).is_err(), "{invalid}");
+        }
+        assert_eq!(parse("18446744073709551615ms").unwrap(), u64::MAX);
+        assert_eq!(parse("18446744073709551615s").unwrap_err().to_string(), "duration is too long");
+    }
+}
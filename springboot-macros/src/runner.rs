@@ -0,0 +1,73 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+
+struct Runner {
+    /// Registered as a `CommandLineRunner` instead of an `ApplicationRunner`.
+    command_line: bool,
+
+    /// AST of the runner struct being annotated.
+    ast: syn::ItemStruct,
+}
+
+impl Runner {
+    fn new(args: TokenStream, ast: syn::ItemStruct) -> syn::Result<Self> {
+        let command_line = if args.is_empty() {
+            false
+        } else {
+            let ident = syn::parse2::<syn::Ident>(args)?;
+            if ident != "command_line" {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "invalid runner definition, expected #[runner] or #[runner(command_line)]",
+                ));
+            }
+            true
+        };
+        Ok(Self { command_line, ast })
+    }
+}
+
+impl ToTokens for Runner {
+    fn to_tokens(&self, output: &mut TokenStream) {
+        let Self { command_line, ast } = self;
+        let ident = &ast.ident;
+        let runner_registrar =
+            syn::Ident::new(&format!("__RunnerRegistrarFor_{ident}"), ident.span());
+        let runner = if *command_line {
+            quote!(::springboot::runner::RunnerRef::CommandLine(::std::sync::Arc::new(runner)))
+        } else {
+            quote!(::springboot::runner::RunnerRef::Application(::std::sync::Arc::new(runner)))
+        };
+
+        output.extend(quote! {
+            #ast
+
+            #[allow(non_camel_case_types)]
+            struct #runner_registrar;
+            impl ::springboot::runner::RunnerRegistrar for #runner_registrar {
+                fn name(&self) -> &'static str {
+                    ::std::any::type_name::<#ident>()
+                }
+
+                fn resolve(
+                    &self,
+                    app: &::springboot::application::App,
+                ) -> ::std::option::Option<::springboot::runner::RunnerRef> {
+                    let runner = ::springboot::component::ComponentRegistry::get_component::<#ident>(app)?;
+                    ::std::option::Option::Some(#runner)
+                }
+            }
+            ::springboot::submit_runner!(#runner_registrar);
+        });
+    }
+}
+
+pub(crate) fn with_runner(args: TokenStream, input: syn::ItemStruct) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "generic struct can't be registered as runner",
+        ));
+    }
+    Ok(Runner::new(args, input)?.into_token_stream())
+}
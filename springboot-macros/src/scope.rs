@@ -1,10 +1,11 @@
 use crate::{
     input_and_compile_error,
-    route::{MethodType, RouteArgs},
+    route::{parse_layer, MethodType, RouteArgs},
 };
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{quote, ToTokens as _};
+use std::collections::HashMap;
 
 pub(crate) fn with_scope(args: TokenStream, input: TokenStream) -> TokenStream {
     match with_scope_inner(args, input.clone()) {
@@ -13,6 +14,53 @@ pub(crate) fn with_scope(args: TokenStream, input: TokenStream) -> TokenStream {
     }
 }
 
+/// `"/prefix", layer = .., timeout = "..", fallback = handler`
+struct ScopeArgs {
+    prefix: syn::LitStr,
+    /// `layer` and `timeout` options, applied once to the router of the scope
+    layers: Vec<syn::MetaNameValue>,
+    fallback: Option<syn::Expr>,
+    /// Registrar of the outer scope, set by the outer scope
+    parent: Option<syn::Path>,
+}
+
+impl ScopeArgs {
+    fn new(args: RouteArgs) -> syn::Result<Self> {
+        let mut layers = vec![];
+        let mut fallback = None;
+        let mut parent = None;
+        for option in args.options {
+            if option.path.is_ident("fallback") {
+                fallback = Some(option.value);
+            } else if let (true, syn::Expr::Path(path)) = (option.path.is_ident("__scope"), &option.value) {
+                parent = Some(path.path.clone());
+            } else if parse_layer(&option)?.is_some() {
+                layers.push(option);
+            } else {
+                return Err(syn::Error::new_spanned(
+                    &option.path,
+                    "Unknown attribute key is specified; allowed: layer, timeout, fallback",
+                ));
+            }
+        }
+        if args.path.value().ends_with('/') {
+            // trailing slashes cause non-obvious problems
+            // it's better to point them out to developers rather than
+
+            return Err(syn::Error::new(
+                args.path.span(),
+                "scopes should not have trailing slashes; see https://docs.rs/actix-web/4/actix_web/struct.Scope.html#avoid-trailing-slashes",
+            ));
+        }
+        Ok(Self {
+            prefix: args.path,
+            layers,
+            fallback,
+            parent,
+        })
+    }
+}
+
 fn with_scope_inner(args: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
     if args.is_empty() {
         return Err(syn::Error::new(
@@ -21,24 +69,14 @@ fn with_scope_inner(args: TokenStream, input: TokenStream) -> syn::Result<TokenS
         ));
     }
 
-    let scope_prefix = syn::parse::<syn::LitStr>(args.clone()).map_err(|err| {
+    let scope_args = syn::parse::<RouteArgs>(args).map_err(|err| {
         syn::Error::new(
             err.span(),
             "argument to scope macro is not a string literal, expected: #[scope(\"/prefix\")]",
         )
     })?;
-
-    let scope_prefix_value = scope_prefix.value();
-
-    if scope_prefix_value.ends_with('/') {
-        // trailing slashes cause non-obvious problems
-        // it's better to point them out to developers rather than
-
-        return Err(syn::Error::new(
-            scope_prefix.span(),
-            "scopes should not have trailing slashes; see https://docs.rs/actix-web/4/actix_web/struct.Scope.html#avoid-trailing-slashes",
-        ));
-    }
+    let scope_args = ScopeArgs::new(scope_args)?;
+    let scope_prefix_value = scope_args.prefix.value();
 
     let mut module = syn::parse::<syn::ItemMod>(input).map_err(|err| {
         syn::Error::new(err.span(), "#[scope] macro must be attached to a module")
@@ -47,22 +85,242 @@ fn with_scope_inner(args: TokenStream, input: TokenStream) -> syn::Result<TokenS
     // modify any routing macros (method or route[s]) attached to
     // functions by prefixing them with this scope macro's argument
     if let Some((_, items)) = &mut module.content {
-        for item in items {
-            if let syn::Item::Fn(fun) = item {
+        let mut routes = RouteTable::default();
+        rewrite_items(items, &scope_prefix_value, &syn::parse_quote!(__scope), &mut routes)?;
+
+        let mut fallback_paths = vec![];
+        let fallback = scope_args.fallback.as_ref().map(|fallback| {
+            let path = format!("{scope_prefix_value}/{{*__rest}}");
+            if routes.has_path(&path) {
+                return Err(syn::Error::new_spanned(
+                    fallback,
+                    format!("the fallback of the scope conflicts with the route `{path}`"),
+                ));
+            }
+            // the prefix itself is answered by the fallback too, unless a handler has it
+            if !scope_prefix_value.is_empty() && !routes.has_path(&scope_prefix_value) {
+                fallback_paths.push(scope_prefix_value.clone());
+            }
+            fallback_paths.push(path);
+            Ok(quote!(::springboot_web::axum::routing::any(#fallback)))
+        });
+        let fallback = fallback.transpose()?.map(|method_router| {
+            quote! {
+                let __method_router = #method_router;
+                #(
+                    __scope_router = ::springboot_web::Router::route(__scope_router, #fallback_paths, __method_router.clone());
+                )*
+            }
+        });
+        let layers = scope_args
+            .layers
+            .iter()
+            .map(parse_layer)
+            .collect::<syn::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten();
+        let submit = match &scope_args.parent {
+            Some(parent) => quote!(::springboot_web::submit_scoped_handler!(__scope, #parent);),
+            None => quote!(::springboot_web::submit_typed_handler!(__scope);),
+        };
+        items.push(syn::parse_quote! {
+            #[allow(non_camel_case_types, missing_docs)]
+            struct __scope;
+        });
+        items.push(syn::parse_quote! {
+            impl ::springboot_web::handler::HandlerRegistrar for __scope {
+                fn register(&self, __router: ::springboot_web::Router) -> ::springboot_web::Router {
+                    // the handlers of the scope share one router, its layers are created once
+                    let mut __scope_router = ::springboot_web::Router::new();
+                    for __handler in ::springboot_web::handler::scoped_handlers(::std::any::type_name::<Self>()) {
+                        __scope_router = __handler.register(__scope_router);
+                    }
+                    #fallback
+                    if ::springboot_web::Router::has_routes(&__scope_router) {
+                        #(
+                            __scope_router = ::springboot_web::Router::route_layer(__scope_router, #layers);
+                        )*
+                    }
+                    ::springboot_web::Router::merge(__router, __scope_router)
+                }
+
+                fn fallback_paths(&self) -> ::std::vec::Vec<&'static str> {
+                    ::std::vec![#(#fallback_paths),*]
+                }
+            }
+        });
+        items.push(syn::parse_quote!(#submit));
+    }
+
+    Ok(module.to_token_stream().into())
+}
+
+/// Routes of a scope and its nested modules, by method and path without parameter names
+#[derive(Default)]
+struct RouteTable(HashMap<(String, String), (String, syn::Ident)>);
+
+impl RouteTable {
+    fn insert(&mut self, method: String, path: String, handler: &syn::Ident) -> syn::Result<()> {
+        let key = (method.clone(), route_key(&path));
+        match self.0.get(&key) {
+            Some((other_path, other)) => Err(syn::Error::new_spanned(
+                handler,
+                format!("duplicate route `{method} {path}`, already handled by `{other}` as `{other_path}`"),
+            )),
+            None => {
+                self.0.insert(key, (path, handler.clone()));
+                Ok(())
+            }
+        }
+    }
+
+    fn has_path(&self, path: &str) -> bool {
+        let key = route_key(path);
+        self.0.keys().any(|(_, p)| *p == key)
+    }
+
+    /// Add the routes of the already prefixed attributes of a handler
+    fn add_handler(&mut self, attrs: &[syn::Attribute], handler: &syn::Ident) -> syn::Result<()> {
+        for attr in attrs.iter().filter(|attr| has_allowed_methods_in_scope(attr)) {
+            let Ok(route_args) = attr.parse_args::<RouteArgs>() else {
+                continue;
+            };
+            let path = route_args.path.value();
+            if let Ok(method) = MethodType::from_path(attr.path()) {
+                self.insert(method.as_str().to_uppercase(), path.clone(), handler)?;
+            }
+            for option in route_args.options.iter().filter(|option| option.path.is_ident("method")) {
+                if let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(method),
+                    ..
+                }) = &option.value
+                {
+                    self.insert(method.value(), path.clone(), handler)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The path with unnamed parameters, `/post/{id}` and `/post/{pid}` are the same route
+fn route_key(path: &str) -> String {
+    let mut key = String::with_capacity(path.len());
+    let mut in_param = false;
+    for c in path.chars() {
+        match c {
+            '{' => {
+                in_param = true;
+                key.push(c);
+            }
+            '}' => {
+                in_param = false;
+                key.push(c);
+            }
+            '*' if in_param => key.push(c),
+            _ if in_param => {}
+            _ => key.push(c),
+        }
+    }
+    key
+}
+
+fn is_scope(attr: &syn::Attribute) -> bool {
+    attr.path().segments.last().is_some_and(|segment| segment.ident == "scope")
+}
+
+fn is_controller(attr: &syn::Attribute) -> bool {
+    attr.path().segments.last().is_some_and(|segment| segment.ident == "controller")
+}
+
+/// Path of the registrar of the scope from a module nested in the current one
+fn nested_scope(scope: &syn::Path) -> syn::Path {
+    let mut nested: syn::Path = syn::parse_quote!(super);
+    nested.segments.extend(scope.segments.iter().cloned());
+    nested
+}
+
+/// Prefix the routes of the functions, the methods of impl blocks and the nested modules,
+/// they are registered by the given registrar of the scope
+fn rewrite_items(
+    items: &mut [syn::Item],
+    scope_path: &str,
+    scope: &syn::Path,
+    routes: &mut RouteTable,
+) -> syn::Result<()> {
+    for item in items {
+        match item {
+            syn::Item::Fn(fun) => {
                 fun.attrs = fun
                     .attrs
                     .iter()
-                    .map(|attr| modify_attribute_with_scope(attr, &scope_prefix_value))
+                    .map(|attr| modify_attribute_with_scope(attr, scope_path, scope))
                     .collect();
+                routes.add_handler(&fun.attrs, &fun.sig.ident)?;
+            }
+            syn::Item::Struct(item_struct) => {
+                // the controller prefixes the paths of its methods
+                if let Some(attr) = item_struct.attrs.iter_mut().find(|attr| is_controller(attr)) {
+                    let args = attr.parse_args::<RouteArgs>()?;
+                    let prefix = format!("{}{}", scope_path, args.path.value());
+                    let path = attr.path().clone();
+                    *attr = syn::parse_quote!(#[#path(#prefix)]);
+                }
             }
+            syn::Item::Impl(item_impl) => {
+                let is_controller = item_impl.attrs.iter().any(is_controller);
+                for impl_item in &mut item_impl.items {
+                    if let syn::ImplItem::Fn(fun) = impl_item {
+                        if is_controller {
+                            // the prefix is only known at runtime, the methods are registered by the scope
+                            fun.attrs = fun
+                                .attrs
+                                .iter()
+                                .map(|attr| modify_attribute_with_scope(attr, "", scope))
+                                .collect();
+                            continue;
+                        }
+                        fun.attrs = fun
+                            .attrs
+                            .iter()
+                            .map(|attr| modify_attribute_with_scope(attr, scope_path, scope))
+                            .collect();
+                        routes.add_handler(&fun.attrs, &fun.sig.ident)?;
+                    }
+                }
+            }
+            syn::Item::Mod(nested) => {
+                let Some((_, nested_items)) = &mut nested.content else {
+                    continue;
+                };
+                let scope = nested_scope(scope);
+                let Some(index) = nested.attrs.iter().position(is_scope) else {
+                    rewrite_items(nested_items, scope_path, &scope, routes)?;
+                    continue;
+                };
+                // the nested scope prefixes its own routes when it is expanded,
+                // its attribute gets the composed prefix and its router is registered by this scope
+                let attr = &nested.attrs[index];
+                let nested_args = ScopeArgs::new(attr.parse_args::<RouteArgs>()?)?;
+                let prefix = format!("{}{}", scope_path, nested_args.prefix.value());
+                rewrite_items(&mut nested_items.clone(), &prefix, &syn::parse_quote!(__scope), routes)?;
+
+                let nested_layers = &nested_args.layers;
+                let fallback = nested_args.fallback.iter();
+                let path = attr.path().clone();
+                nested.attrs[index] = syn::parse_quote! {
+                    #[#path(#prefix #(, #nested_layers)* #(, fallback = #fallback)*, __scope = #scope)]
+                };
+            }
+            _ => {}
         }
     }
-
-    Ok(module.to_token_stream().into())
+    Ok(())
 }
 
 /// Checks if the attribute is a method type and has a route path, then modifies it.
-fn modify_attribute_with_scope(attr: &syn::Attribute, scope_path: &str) -> syn::Attribute {
+/// The handler is registered by the scope, which layers its router around the layers of the handler.
+fn modify_attribute_with_scope(attr: &syn::Attribute, scope_path: &str, scope: &syn::Path) -> syn::Attribute {
     match (attr.parse_args::<RouteArgs>(), attr.clone().meta) {
         (Ok(route_args), syn::Meta::List(meta_list)) if has_allowed_methods_in_scope(attr) => {
             let modified_path = format!("{}{}", scope_path, route_args.path.value());
@@ -73,6 +331,7 @@ fn modify_attribute_with_scope(attr: &syn::Attribute, scope_path: &str) -> syn::
                 .map(|option| {
                     quote! { ,#option }
                 })
+                .chain(std::iter::once(quote! { , __scope = #scope }))
                 .collect();
 
             let combined_options_tokens: TokenStream2 =
@@ -97,6 +356,9 @@ fn modify_attribute_with_scope(attr: &syn::Attribute, scope_path: &str) -> syn::
 
 fn has_allowed_methods_in_scope(attr: &syn::Attribute) -> bool {
     MethodType::from_path(attr.path()).is_ok()
-        || attr.path().is_ident("route")
-        || attr.path().is_ident("ROUTE")
+        || attr
+            .path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "route" || segment.ident == "ROUTE")
 }
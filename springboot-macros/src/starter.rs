@@ -0,0 +1,81 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+
+struct Starter {
+    /// Installed through `AppBuilder::logger` instead of `AppBuilder::add_starter`.
+    logger: bool,
+
+    /// AST of the starter struct being annotated.
+    ast: syn::ItemStruct,
+}
+
+impl Starter {
+    fn new(args: TokenStream, ast: syn::ItemStruct) -> syn::Result<Self> {
+        let logger = if args.is_empty() {
+            false
+        } else {
+            let ident = syn::parse2::<syn::Ident>(args)?;
+            if ident != "logger" {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "invalid starter definition, expected #[starter] or #[starter(logger)]",
+                ));
+            }
+            true
+        };
+        Ok(Self { logger, ast })
+    }
+}
+
+impl ToTokens for Starter {
+    fn to_tokens(&self, output: &mut TokenStream) {
+        let Self { logger, ast } = self;
+        let ident = &ast.ident;
+        let starter_registrar =
+            syn::Ident::new(&format!("__StarterRegistrarFor_{ident}"), ident.span());
+        let instance = match ast.fields {
+            syn::Fields::Unit => quote!(#ident),
+            _ => quote!(<#ident as ::std::default::Default>::default()),
+        };
+        let install = if *logger {
+            quote! {
+                if app.logger_handler.is_none() {
+                    app.logger(#instance);
+                }
+            }
+        } else {
+            quote! {
+                if !app.is_plugin_added::<#ident>() {
+                    app.add_starter(#instance);
+                }
+            }
+        };
+
+        output.extend(quote! {
+            #ast
+
+            #[allow(non_camel_case_types)]
+            struct #starter_registrar;
+            impl ::springboot::component::StarterRegistrar for #starter_registrar {
+                fn name(&self) -> &'static str {
+                    ::std::any::type_name::<#ident>()
+                }
+
+                fn install_starter(&self, app: &mut ::springboot::application::AppBuilder) {
+                    #install
+                }
+            }
+            ::springboot::submit_starter!(#starter_registrar);
+        });
+    }
+}
+
+pub(crate) fn with_starter(args: TokenStream, input: syn::ItemStruct) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "generic struct can't be registered as starter",
+        ));
+    }
+    Ok(Starter::new(args, input)?.into_token_stream())
+}
@@ -0,0 +1,55 @@
+use springboot::executor::AsyncMethod;
+use springboot::testing::TestApp;
+use springboot_macros::async_method;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+#[derive(Clone)]
+struct MailService;
+
+impl MailService {
+    #[async_method]
+    async fn count(&self, to: Vec<String>) -> Result<usize, String> {
+        Ok(to.len())
+    }
+
+    #[async_method(pool = "mail")]
+    async fn send_mail(&self, to: String) -> Result<(), String> {
+        Err(format!("smtp down: {to}"))
+    }
+
+    #[async_method(pool = "missing")]
+    async fn archive(&self) {}
+}
+
+#[tokio::test]
+async fn test_async_method() {
+    let errors = Arc::new(Mutex::new(vec![]));
+    let reported = errors.clone();
+    let handled = Arc::new(Notify::new());
+    let notify = handled.clone();
+    let app = TestApp::build(
+        TestApp::builder("[executor.pools.mail]\nmax_concurrency = 1").uncaught_error_handler(
+            move |method: &AsyncMethod, error: &dyn std::fmt::Debug| {
+                reported.lock().unwrap().push(format!("{}: {error:?}", method.name));
+                notify.notify_one();
+            },
+        ),
+    )
+    .await
+    .unwrap();
+
+    let service = MailService;
+    let to = vec!["a@b.c".to_string(), "d@e.f".to_string()];
+    assert_eq!(service.count(to).await.unwrap(), Ok(2));
+    assert!(service.archive().await.is_err());
+
+    // the error of a dropped result goes to the uncaught error handler
+    drop(service.send_mail("a@b.c".to_string()));
+    tokio::time::timeout(Duration::from_secs(5), handled.notified())
+        .await
+        .expect("the error is reported");
+    assert_eq!(*errors.lock().unwrap(), vec!["send_mail: \"smtp down: a@b.c\"".to_string()]);
+    app.shutdown().await.unwrap();
+}
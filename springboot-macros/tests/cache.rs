@@ -0,0 +1,74 @@
+use springboot::cache::CacheManager;
+use springboot::error::{AppError, Result};
+use springboot::testing::TestApp;
+use springboot_macros::{cache_evict, cache_put, cacheable};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone, Default)]
+struct UserService {
+    calls: Arc<AtomicUsize>,
+}
+
+impl UserService {
+    #[cacheable("users", key = "id")]
+    async fn name(&self, id: u64) -> Result<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if id == 0 {
+            return Err(AppError::ComponentNotExist("user"));
+        }
+        Ok(format!("user-{id}"))
+    }
+
+    #[cacheable("users")]
+    async fn email(&self, id: u64) -> String {
+        format!("user-{id}@example.com")
+    }
+
+    #[cacheable("users")]
+    async fn phone(&self, id: u64) -> String {
+        format!("+{id}")
+    }
+
+    #[cache_put("users", key = "id")]
+    async fn rename(&self, id: u64, name: String) -> Result<String> {
+        Ok(name)
+    }
+
+    #[cache_evict("users", key = "id")]
+    async fn delete(&self, id: u64) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_cache() {
+    let app = TestApp::build(&mut TestApp::builder("")).await.unwrap();
+    let service = UserService::default();
+    let calls = || service.calls.load(Ordering::SeqCst);
+
+    assert_eq!(service.name(1).await.unwrap(), "user-1");
+    assert_eq!(service.name(1).await.unwrap(), "user-1");
+    assert_eq!(calls(), 1);
+    let stats = CacheManager::current().unwrap().stats("users");
+    assert_eq!((stats.hits, stats.misses, stats.puts), (1, 1, 1));
+
+    // errors are not cached
+    assert!(service.name(0).await.is_err());
+    assert!(service.name(0).await.is_err());
+    assert_eq!(calls(), 3);
+
+    assert_eq!(service.rename(1, "bob".to_string()).await.unwrap(), "bob");
+    assert_eq!(service.name(1).await.unwrap(), "bob");
+    assert_eq!(calls(), 3);
+
+    service.delete(1).await.unwrap();
+    assert_eq!(service.name(1).await.unwrap(), "user-1");
+    assert_eq!(calls(), 4);
+
+    // the default keys of functions with the same arguments differ
+    assert_eq!(service.email(2).await, "user-2@example.com");
+    assert_eq!(service.phone(2).await, "+2");
+    assert_eq!(service.email(2).await, "user-2@example.com");
+    app.shutdown().await.unwrap();
+}
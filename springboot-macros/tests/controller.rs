@@ -0,0 +1,62 @@
+use springboot::component::MutableComponentRegistry;
+use springboot::testing::TestApp;
+use springboot::App;
+use springboot_macros::{controller, get, post};
+use springboot_web::axum::body::Body;
+use springboot_web::axum::extract::Path;
+use springboot_web::axum::http::{Request, StatusCode};
+use springboot_web::{AppState, Router};
+use tower::ServiceExt;
+
+#[derive(Clone)]
+struct Greeting(&'static str);
+
+#[controller("/hello")]
+struct HelloController {
+    #[inject(component)]
+    greeting: Greeting,
+}
+
+#[controller]
+impl HelloController {
+    #[get("/{name}")]
+    async fn hello(&self, Path(name): Path<String>) -> String {
+        format!("{} {name}", self.greeting.0)
+    }
+
+    #[post("")]
+    async fn create(&self, body: String) -> String {
+        format!("{} created {body}", self.greeting.0)
+    }
+}
+
+async fn call(router: Router<()>, app: Option<&TestApp>, req: Request<Body>) -> (StatusCode, String) {
+    let response = match app {
+        Some(app) => App::scope(app.app(), router.oneshot(req)).await.unwrap(),
+        None => router.oneshot(req).await.unwrap(),
+    };
+    let status = response.status();
+    let body = springboot_web::axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_controller() {
+    let app = TestApp::build(TestApp::builder("").add_component(Greeting("hi"))).await.unwrap();
+    let router = springboot_web::handler::auto_router().with_state(AppState { app: app.app() });
+
+    let req = Request::get("/hello/bob").body(Body::empty()).unwrap();
+    assert_eq!(call(router.clone(), Some(&app), req).await, (StatusCode::OK, "hi bob".to_string()));
+    let req = Request::post("/hello").body(Body::from("post")).unwrap();
+    assert_eq!(call(router.clone(), Some(&app), req).await, (StatusCode::OK, "hi created post".to_string()));
+
+    // the test app is scoped to this thread, elsewhere the controller is not installed
+    let status = std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let req = Request::get("/hello/bob").body(Body::empty()).unwrap();
+        runtime.block_on(call(router, None, req)).0
+    })
+    .join()
+    .unwrap();
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
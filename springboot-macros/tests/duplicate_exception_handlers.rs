@@ -0,0 +1,38 @@
+use springboot::testing::TestApp;
+use springboot_macros::exception_handler;
+use springboot_web::axum::http::StatusCode;
+use springboot_web::exception::check_exception_handlers;
+use springboot_web::WebStarter;
+
+#[derive(Debug)]
+struct PostNotFound;
+
+impl std::fmt::Display for PostNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "post not found")
+    }
+}
+
+impl std::error::Error for PostNotFound {}
+
+#[exception_handler(PostNotFound)]
+fn not_found(_e: &PostNotFound) -> StatusCode {
+    StatusCode::NOT_FOUND
+}
+
+#[exception_handler(PostNotFound)]
+fn gone(_e: &PostNotFound) -> StatusCode {
+    StatusCode::GONE
+}
+
+#[tokio::test]
+async fn test_duplicate_exception_handlers() {
+    let err = check_exception_handlers().unwrap_err().to_string();
+    assert!(err.starts_with("duplicate exception handler for `"), "{err}");
+    assert!(err.contains(std::any::type_name::<PostNotFound>()), "{err}");
+    assert!(err.contains("`not_found`") && err.contains("`gone`"), "{err}");
+
+    // the web starter does not start
+    let result = TestApp::build(TestApp::builder("").add_starter(WebStarter)).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,76 @@
+use springboot::testing::TestApp;
+use springboot_macros::{get, scope};
+use springboot_web::axum::body::Body;
+use springboot_web::axum::http::{Request, StatusCode};
+use springboot_web::handler::{auto_router, check_routes};
+use springboot_web::WebStarter;
+use tower::ServiceExt;
+
+#[scope("/api")]
+mod v1 {
+    use super::*;
+
+    #[get("/users")]
+    pub async fn users() -> &'static str {
+        "v1"
+    }
+
+    #[get("/users/{id}")]
+    pub async fn user() -> &'static str {
+        "v1"
+    }
+}
+
+// another top level scope, the duplicate is only known when the router is built
+#[scope("/api")]
+mod v2 {
+    use super::*;
+
+    #[get("/users")]
+    pub async fn users() -> &'static str {
+        "v2"
+    }
+
+    // the same route, the parameters only differ by name
+    #[get("/users/{uid}")]
+    pub async fn user() -> &'static str {
+        "v2"
+    }
+}
+
+#[scope("/files", fallback = not_found)]
+mod files {
+    pub async fn not_found() -> &'static str {
+        "not found"
+    }
+}
+
+// overlaps the fallback of the other scope
+#[scope("/files")]
+mod downloads {
+    use super::*;
+
+    #[get("/{*path}")]
+    pub async fn download() -> &'static str {
+        "file"
+    }
+}
+
+#[tokio::test]
+async fn test_duplicate_routes() {
+    let err = check_routes().unwrap_err().to_string();
+    assert!(err.contains("duplicate route `GET /api/users`, handled by both `users` and `users`"), "{err}");
+    assert!(err.contains("handled by both `user` and `user`"), "{err}");
+    assert!(err.contains("/files/{*"), "{err}");
+
+    // the router is still built, with one of the handlers
+    let router = auto_router().with_state(springboot_web::AppState {
+        app: std::sync::Arc::new(springboot::App::default()),
+    });
+    let response = router.oneshot(Request::get("/api/users").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // the web starter does not start
+    let result = TestApp::build(TestApp::builder("").add_starter(WebStarter)).await;
+    assert!(result.is_err());
+}
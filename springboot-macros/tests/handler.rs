@@ -0,0 +1,73 @@
+use springboot::component::{ComponentRegistry, MutableComponentRegistry};
+use springboot::error::AppError;
+use springboot::testing::TestApp;
+use springboot_macros::{controller, get};
+use springboot_web::extractor::{Config, Inject};
+use springboot_web::handler::{check_dependencies, HandlerRegistrar};
+use springboot_web::WebStarter;
+
+#[derive(Clone)]
+struct Clock;
+
+#[derive(Clone)]
+struct Mailer;
+
+#[get("/mail")]
+async fn mail(_mailer: Inject<Mailer>, _config: Config<springboot_web::config::WebConfig>) -> &'static str {
+    "sent"
+}
+
+#[controller("/report")]
+struct ReportController {
+    #[inject(component)]
+    _clock: Clock,
+}
+
+#[controller]
+impl ReportController {
+    #[get("")]
+    async fn report(&self, _mailer: Inject<Mailer>) -> &'static str {
+        "report"
+    }
+}
+
+fn type_names(dependencies: Vec<springboot_web::extractor::Dependency>) -> Vec<&'static str> {
+    dependencies.into_iter().map(|d| d.type_name).collect()
+}
+
+#[test]
+fn test_dependencies() {
+    assert_eq!(type_names(mail.dependencies()), [std::any::type_name::<Mailer>()]);
+    // the controller is resolved for each request
+    assert_eq!(
+        type_names(__ReportController_report.dependencies()),
+        [std::any::type_name::<ReportController>(), std::any::type_name::<Mailer>()]
+    );
+}
+
+#[test]
+fn test_check_dependencies() {
+    let mut builder = TestApp::builder("");
+    let err = check_dependencies(&builder).unwrap_err().to_string();
+    assert!(err.contains(&format!("`{}` required by `{}`", std::any::type_name::<Mailer>(), mail.name())));
+    assert!(err.contains(std::any::type_name::<ReportController>()));
+
+    builder.add_component(Mailer);
+    let err = check_dependencies(&builder).unwrap_err().to_string();
+    assert!(!err.contains(&format!("`{}`", std::any::type_name::<Mailer>())));
+    assert!(err.contains(std::any::type_name::<ReportController>()));
+}
+
+#[tokio::test]
+async fn test_missing_dependency_fails_build() {
+    let err = TestApp::build(TestApp::builder("").add_starter(WebStarter).add_component(Clock))
+        .await
+        .err()
+        .expect("the handlers inject a missing component");
+    assert!(matches!(&err, AppError::OtherError(e) if e.to_string().contains(std::any::type_name::<Mailer>())));
+
+    let app = TestApp::build(TestApp::builder("").add_starter(WebStarter).add_component(Clock).add_component(Mailer))
+        .await
+        .unwrap();
+    assert!(app.app().has_component::<ReportController>());
+}
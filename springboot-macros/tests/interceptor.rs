@@ -0,0 +1,113 @@
+use springboot::component::MutableComponentRegistry;
+use springboot::error::{AppError, Result};
+use springboot::interceptor::{around, Interceptor, Invocation, MethodResult, Proceed};
+use springboot::testing::TestApp;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct Calls(Arc<Mutex<Vec<String>>>);
+
+#[derive(Clone, Default)]
+struct Audit(Calls);
+
+impl Interceptor for Audit {
+    async fn around<R: MethodResult>(&self, invocation: &Invocation, proceed: Proceed<'_, R>) -> Result<R> {
+        let args = format!("{:?}", proceed.args());
+        let result = proceed.proceed().await;
+        self.0 .0.lock().unwrap().push(format!("audit {}{args}", invocation.method));
+        Ok(result)
+    }
+}
+
+#[derive(Clone, Default)]
+struct Timing(Calls);
+
+impl Interceptor for Timing {
+    async fn around<R: MethodResult>(&self, invocation: &Invocation, proceed: Proceed<'_, R>) -> Result<R> {
+        let result = proceed.proceed().await;
+        self.0 .0.lock().unwrap().push(format!("timing {}", invocation.method));
+        Ok(result)
+    }
+}
+
+/// Counts how often it is formatted
+struct Secret(Arc<Mutex<usize>>);
+
+impl Secret {
+    fn formatted(&self) -> usize {
+        *self.0.lock().unwrap()
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        *self.0.lock().unwrap() += 1;
+        write!(f, "***")
+    }
+}
+
+#[derive(Clone)]
+struct UserService;
+
+#[around]
+impl UserService {
+    #[around(Timing)]
+    #[around(Audit)]
+    async fn rename(&self, id: u64, mut name: String) -> Result<String> {
+        name.push('!');
+        Ok(format!("{id}:{name}"))
+    }
+
+    #[around(Timing)]
+    async fn login(&self, secret: Secret) -> Result<usize> {
+        Ok(secret.formatted())
+    }
+}
+
+#[tokio::test]
+async fn test_around() {
+    let calls = Calls::default();
+    let _app = TestApp::build(
+        TestApp::builder("")
+            .add_component(Audit(calls.clone()))
+            .add_component(Timing(calls.clone())),
+    )
+    .await
+    .unwrap();
+
+    let renamed = UserService.rename(7, "alice".to_string()).await.unwrap();
+    assert_eq!(renamed, "7:alice!");
+    // the first interceptor is the outermost
+    assert_eq!(
+        *calls.0.lock().unwrap(),
+        ["audit rename[(\"id\", 7), (\"name\", \"alice\")]", "timing rename"]
+    );
+
+    // the arguments are not formatted by the interceptors that don't log them
+    let secret = Secret(Arc::new(Mutex::new(0)));
+    assert_eq!(UserService.login(secret).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_missing_interceptor_fails_build() {
+    let err = TestApp::build(
+        TestApp::builder("")
+            .add_component(UserService)
+            .add_component(Timing::default()),
+    )
+        .await
+        .err()
+        .expect("the app has no Audit interceptor");
+    assert!(matches!(&err, AppError::OtherError(e) if e.to_string().contains(&format!(
+        "`{}` required by `interceptor::rename`",
+        std::any::type_name::<Audit>()
+    ))), "{err}");
+}
+
+#[tokio::test]
+async fn test_interceptor_not_required_without_owner() {
+    // the app doesn't install UserService, so it needs none of its interceptors
+    let app = TestApp::build(&mut TestApp::builder("")).await;
+    assert!(app.is_ok(), "{:?}", app.err());
+}
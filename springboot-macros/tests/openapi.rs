@@ -0,0 +1,85 @@
+use springboot_macros::{get, post};
+use springboot_web::axum::extract::{Path, Query};
+use springboot_web::extractor::Valid;
+use springboot_web::openapi::schemars::{self, JsonSchema};
+use springboot_web::openapi::OpenApi;
+use validator::Validate;
+use springboot_web::axum::Json;
+
+#[derive(serde::Deserialize, serde::Serialize, JsonSchema, Validate)]
+struct Post {
+    #[validate(length(min = 1))]
+    title: String,
+}
+
+#[derive(serde::Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct Page {
+    page: u32,
+    size: Option<u32>,
+}
+
+/// Find a post
+///
+/// by id
+#[get("/post/{id}")]
+async fn get_post(Path(_id): Path<String>, Query(_page): Query<Page>) -> Json<Post> {
+    Json(Post { title: "hi".to_string() })
+}
+
+#[post("/post")]
+async fn create_post(Valid(Json(post)): Valid<Json<Post>>) -> Json<Post> {
+    Json(post)
+}
+
+#[post("/draft")]
+async fn save_draft(Json(post): Json<Post>) -> Json<Post> {
+    Json(post)
+}
+
+#[get("/health")]
+async fn health() -> &'static str {
+    "ok"
+}
+
+#[test]
+fn test_operations() {
+    let json: serde_json::Value = serde_json::from_str(&OpenApi::from_handlers("blog", "1.0.0").to_json()).unwrap();
+    let responses = |path: &str, method: &str| {
+        let mut codes: Vec<String> = json["paths"][path][method]["responses"].as_object().unwrap().keys().cloned().collect();
+        codes.sort();
+        codes
+    };
+
+    let operation = &json["paths"]["/post/{id}"]["get"];
+    assert_eq!(operation["operationId"], "get_post");
+    assert_eq!(operation["summary"], "Find a post");
+    assert_eq!(operation["description"], "by id");
+    assert_eq!(operation["parameters"][0]["name"], "id");
+    assert_eq!(operation["parameters"][0]["in"], "path");
+    assert_eq!(operation["parameters"][0]["schema"]["type"], "string");
+    assert_eq!(operation["parameters"][1]["name"], "page");
+    assert_eq!(operation["parameters"][1]["in"], "query");
+    assert_eq!(operation["parameters"][1]["required"], true);
+    assert_eq!(operation["parameters"][2]["required"], false);
+    assert!(operation.get("requestBody").is_none());
+    assert_eq!(
+        operation["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Post"
+    );
+    assert_eq!(responses("/post/{id}", "get"), ["200", "400", "500"]);
+
+    // the body of Valid<Json<T>> is documented like the one of Json<T>, with the validation failure
+    let body = &json["paths"]["/post"]["post"];
+    assert_eq!(body["requestBody"]["required"], true);
+    assert_eq!(
+        body["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Post"
+    );
+    assert_eq!(responses("/post", "post"), ["200", "400", "422", "500"]);
+    assert_eq!(responses("/draft", "post"), ["200", "400", "500"]);
+
+    assert!(json["paths"]["/health"]["get"]["responses"]["200"].get("content").is_none());
+    assert_eq!(responses("/health", "get"), ["200", "500"]);
+    assert_eq!(json["components"]["schemas"]["Post"]["properties"]["title"]["type"], "string");
+}
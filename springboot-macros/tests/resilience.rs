@@ -0,0 +1,64 @@
+use springboot::error::AppError;
+use springboot::resilience::{CircuitState, Resilience};
+use springboot::testing::TestApp;
+use springboot_macros::circuit_breaker;
+
+#[derive(Debug)]
+enum StockError {
+    InvalidSku,
+    Unavailable,
+    Rejected(AppError),
+}
+
+impl From<AppError> for StockError {
+    fn from(e: AppError) -> Self {
+        Self::Rejected(e)
+    }
+}
+
+fn is_outage(e: &StockError) -> bool {
+    matches!(e, StockError::Unavailable)
+}
+
+struct StockService;
+
+impl StockService {
+    #[circuit_breaker("stock", record_on = "is_outage")]
+    async fn stock(&self, sku: &str) -> Result<u64, StockError> {
+        match sku {
+            "" => Err(StockError::InvalidSku),
+            "down" => Err(StockError::Unavailable),
+            sku => Ok(sku.len() as u64),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_circuit_breaker() {
+    let app = TestApp::build(&mut TestApp::builder(
+        r#"
+        [resilience.stock]
+        sliding_window = 2
+        minimum_calls = 2
+        open_duration = 60
+        "#,
+    ))
+    .await
+    .unwrap();
+    let breaker = Resilience::current().unwrap().circuit_breaker("stock");
+
+    // the errors of invalid arguments are not recorded
+    for _ in 0..4 {
+        assert!(matches!(StockService.stock("").await, Err(StockError::InvalidSku)));
+    }
+    assert_eq!(breaker.metrics().buffered_calls, 0);
+    assert_eq!(StockService.stock("sku").await.unwrap(), 3);
+
+    assert!(matches!(StockService.stock("down").await, Err(StockError::Unavailable)));
+    assert_eq!(breaker.state(), CircuitState::Open);
+    assert!(matches!(
+        StockService.stock("sku").await,
+        Err(StockError::Rejected(AppError::CallNotPermitted(name))) if name == "stock"
+    ));
+    app.shutdown().await.unwrap();
+}
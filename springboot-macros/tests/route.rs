@@ -0,0 +1,109 @@
+use springboot_macros::{get, scope};
+use springboot_web::axum::body::Body;
+use springboot_web::axum::http::{HeaderValue, Request, StatusCode};
+use springboot_web::axum::middleware::map_response;
+use springboot_web::axum::response::Response;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tower::ServiceExt;
+
+/// Appends the name of the layer to the `x-layers` header of the response
+fn trace(name: &'static str) -> impl Fn(Response) -> std::future::Ready<Response> + Clone {
+    move |mut response: Response| {
+        let layers = match response.headers().get("x-layers") {
+            Some(layers) => format!("{},{name}", layers.to_str().unwrap()),
+            None => name.to_string(),
+        };
+        response.headers_mut().insert("x-layers", HeaderValue::from_str(&layers).unwrap());
+        std::future::ready(response)
+    }
+}
+
+#[scope("/layers", layer = map_response(trace("scope")))]
+mod layers {
+    use super::*;
+
+    #[get("/traced", layer = map_response(trace("inner")), layer = map_response(trace("outer")))]
+    pub async fn traced() -> &'static str {
+        "traced"
+    }
+
+    #[get("/plain")]
+    pub async fn plain() -> &'static str {
+        "plain"
+    }
+
+    #[get("/slow", timeout = "10ms")]
+    pub async fn slow() -> &'static str {
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        "slow"
+    }
+}
+
+/// Tags the responses with the number of the layer instance
+fn instance() -> impl Fn(Response) -> std::future::Ready<Response> + Clone {
+    static INSTANCES: AtomicUsize = AtomicUsize::new(0);
+    let instance = INSTANCES.fetch_add(1, Ordering::SeqCst);
+    move |mut response: Response| {
+        response.headers_mut().insert("x-instance", HeaderValue::from(instance));
+        std::future::ready(response)
+    }
+}
+
+#[scope("/shared", layer = map_response(instance()))]
+mod shared {
+    use super::*;
+
+    #[get("/first")]
+    pub async fn first() -> &'static str {
+        "first"
+    }
+
+    #[get("/second")]
+    pub async fn second() -> &'static str {
+        "second"
+    }
+}
+
+#[get("/unscoped")]
+async fn unscoped() -> &'static str {
+    "unscoped"
+}
+
+async fn call(uri: &str) -> (StatusCode, Option<String>) {
+    let router = springboot_web::handler::auto_router().with_state(springboot_web::AppState {
+        app: std::sync::Arc::new(springboot::App::default()),
+    });
+    let response = router.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+    let layers = response.headers().get("x-layers").map(|v| v.to_str().unwrap().to_string());
+    (response.status(), layers)
+}
+
+#[tokio::test]
+async fn test_layers() {
+    // the handler's layers in order, then the layers of the scope around them
+    assert_eq!(call("/layers/traced").await, (StatusCode::OK, Some("inner,outer,scope".to_string())));
+    // a handler's layer applies to its own route only
+    assert_eq!(call("/layers/plain").await, (StatusCode::OK, Some("scope".to_string())));
+    assert_eq!(call("/unscoped").await, (StatusCode::OK, None));
+}
+
+#[tokio::test]
+async fn test_timeout() {
+    assert_eq!(call("/layers/slow").await.0, StatusCode::REQUEST_TIMEOUT);
+}
+
+#[tokio::test]
+async fn test_scope_layer_created_once() {
+    let router = springboot_web::handler::auto_router().with_state(springboot_web::AppState {
+        app: std::sync::Arc::new(springboot::App::default()),
+    });
+    let mut instances = vec![];
+    for uri in ["/shared/first", "/shared/second"] {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        instances.push(response.headers().get("x-instance").cloned());
+    }
+    assert!(instances[0].is_some());
+    // the routes of the scope share its layer
+    assert_eq!(instances[0], instances[1]);
+}
@@ -0,0 +1,67 @@
+use springboot::application::App;
+use springboot::async_trait;
+use springboot::component::service::Service;
+use springboot::component::MutableComponentRegistry;
+use springboot::error::Result;
+use springboot::runner::{ApplicationArguments, ApplicationRunner, CommandLineRunner};
+use springboot_macros::{command, runner};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+struct Calls(Arc<Mutex<Vec<String>>>);
+
+#[derive(Clone, Service)]
+#[runner]
+struct WarmUp {
+    #[inject(component)]
+    calls: Calls,
+}
+
+#[async_trait]
+impl ApplicationRunner for WarmUp {
+    async fn run(&self, _app: Arc<App>, args: &ApplicationArguments) -> Result<()> {
+        let names = args.non_option_args().join(" ");
+        self.calls.0.lock().unwrap().push(format!("warm-up:{names}"));
+        Ok(())
+    }
+
+    fn order(&self) -> i32 {
+        -1
+    }
+}
+
+#[derive(Clone, Service)]
+#[runner(command_line)]
+struct Report {
+    #[inject(component)]
+    calls: Calls,
+}
+
+#[async_trait]
+impl CommandLineRunner for Report {
+    async fn run(&self, _app: Arc<App>, args: &[String]) -> Result<()> {
+        self.calls.0.lock().unwrap().push(format!("report:{}", args.join(" ")));
+        Ok(())
+    }
+}
+
+#[command("reindex")]
+async fn reindex() -> Result<()> {
+    panic!("the command is only selected with `reindex` or --command=reindex")
+}
+
+#[tokio::test]
+async fn test_runner_components() {
+    let calls = Calls::default();
+    App::new()
+        // the arguments select no command
+        .use_args(ApplicationArguments::new(["--batch=10"]))
+        .add_component(calls.clone())
+        .run()
+        .await;
+
+    assert_eq!(
+        *calls.0.lock().unwrap(),
+        vec!["warm-up:".to_string(), "report:--batch=10".to_string()]
+    );
+}
@@ -0,0 +1,78 @@
+use springboot_macros::{get, post, scope};
+use springboot_web::axum::body::Body;
+use springboot_web::axum::http::{Request, StatusCode};
+use tower::ServiceExt;
+
+#[scope("/api", fallback = not_found)]
+mod api {
+    use super::*;
+
+    pub async fn not_found() -> (StatusCode, &'static str) {
+        (StatusCode::NOT_FOUND, "no such api")
+    }
+
+    #[scope("/v1")]
+    pub mod v1 {
+        use super::*;
+
+        #[get("/users")]
+        pub async fn users() -> &'static str {
+            "users"
+        }
+
+        #[scope("/admin")]
+        pub mod admin {
+            use super::*;
+
+            #[post("/users")]
+            pub async fn create_user() -> &'static str {
+                "created"
+            }
+        }
+    }
+}
+
+#[scope("/shop", fallback = closed)]
+mod shop {
+    use super::*;
+
+    pub async fn closed() -> (StatusCode, &'static str) {
+        (StatusCode::NOT_FOUND, "closed")
+    }
+
+    #[get("")]
+    pub async fn index() -> &'static str {
+        "shop"
+    }
+}
+
+async fn call(method: &str, uri: &str) -> (StatusCode, String) {
+    let router = springboot_web::handler::auto_router().with_state(springboot_web::AppState {
+        app: std::sync::Arc::new(springboot::App::default()),
+    });
+    let req = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+    let response = router.oneshot(req).await.unwrap();
+    let status = response.status();
+    let body = springboot_web::axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_nested_scopes() {
+    assert_eq!(call("GET", "/api/v1/users").await, (StatusCode::OK, "users".to_string()));
+    assert_eq!(call("POST", "/api/v1/admin/users").await, (StatusCode::OK, "created".to_string()));
+    assert_eq!(call("GET", "/v1/users").await.0, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_fallback() {
+    let not_found = (StatusCode::NOT_FOUND, "no such api".to_string());
+    assert_eq!(call("GET", "/api/v2/users").await, not_found);
+    assert_eq!(call("DELETE", "/api/v1/admin").await, not_found);
+    assert_eq!(call("GET", "/api").await, not_found);
+    // the handler of the prefix keeps it
+    assert_eq!(call("GET", "/shop").await, (StatusCode::OK, "shop".to_string()));
+    assert_eq!(call("GET", "/shop/cart").await, (StatusCode::NOT_FOUND, "closed".to_string()));
+    // outside of the scopes
+    assert_eq!(call("GET", "/other").await, (StatusCode::NOT_FOUND, String::new()));
+}
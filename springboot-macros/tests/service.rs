@@ -0,0 +1,31 @@
+use springboot::component::service::Service;
+use springboot::error::AppError;
+use springboot::testing::TestApp;
+
+#[derive(Clone)]
+struct Pool;
+
+#[derive(Clone, Service)]
+struct BlogService {
+    #[allow(dead_code)]
+    #[inject(component)]
+    repository: Repository,
+}
+
+#[derive(Clone, Service)]
+struct Repository {
+    #[allow(dead_code)]
+    #[inject(component)]
+    pool: Pool,
+}
+
+#[tokio::test]
+async fn test_missing_dependency() {
+    match TestApp::build(&mut TestApp::builder("")).await {
+        Err(AppError::MissingDependency(service, component)) => {
+            assert_eq!(service, std::any::type_name::<Repository>());
+            assert_eq!(component, std::any::type_name::<Pool>());
+        }
+        other => panic!("expected a missing dependency, got {:?}", other.err()),
+    }
+}
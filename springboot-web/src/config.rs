@@ -1,6 +1,7 @@
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use springboot::config::Configurable;
+use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr};
 use tracing::Level;
 
@@ -11,6 +12,12 @@ pub struct WebConfig {
     #[serde(flatten)]
     pub(crate) server: ServerConfig,
     pub(crate) middlewares: Option<Middlewares>,
+    #[serde(default)]
+    pub(crate) openapi: OpenApiConfig,
+    #[serde(default)]
+    pub(crate) error: ErrorConfig,
+    #[serde(default)]
+    pub(crate) envelope: EnvelopeConfig,
 }
 
 #[derive(Debug, Clone, JsonSchema, Deserialize)]
@@ -33,6 +40,195 @@ fn default_port() -> u16 {
     80
 }
 
+/// `[web.openapi]`, serving the OpenAPI document of the route macros
+#[derive(Debug, Clone, JsonSchema, Deserialize)]
+pub struct OpenApiConfig {
+    /// toggle enable
+    #[serde(default)]
+    pub enable: bool,
+    /// Uri of the json document
+    #[serde(default = "default_openapi_path")]
+    pub path: String,
+    /// Documentation page loading the document
+    #[serde(default)]
+    pub ui: OpenApiUi,
+    /// Uri of the documentation page
+    #[serde(default = "default_openapi_ui_path")]
+    pub ui_path: String,
+    /// Base url of the scripts and styles of the page, such as `/static/swagger-ui` for a page served
+    /// without internet access. The CDN of the ui by default, see [OpenApiUi]
+    pub ui_assets: Option<String>,
+    /// Title of the API, the app name by default
+    pub title: Option<String>,
+    /// Version of the API, the app version by default
+    pub version: Option<String>,
+}
+
+impl Default for OpenApiConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            path: default_openapi_path(),
+            ui: OpenApiUi::default(),
+            ui_path: default_openapi_ui_path(),
+            ui_assets: None,
+            title: None,
+            version: None,
+        }
+    }
+}
+
+/// Documentation page of the OpenAPI document, the page loads its scripts from `ui_assets`
+#[derive(Debug, Default, Clone, Copy, JsonSchema, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenApiUi {
+    /// No page
+    #[default]
+    None,
+    /// Swagger UI, `swagger-ui.css` and `swagger-ui-bundle.js` of `https://unpkg.com/swagger-ui-dist@5` by default
+    Swagger,
+    /// Redoc, `redoc.standalone.js` of `https://cdn.redoc.ly/redoc/latest/bundles` by default
+    Redoc,
+}
+
+fn default_openapi_path() -> String {
+    "/openapi.json".to_string()
+}
+
+fn default_openapi_ui_path() -> String {
+    "/docs".to_string()
+}
+
+/// `[web.error]`, rendering of the errors returned by handlers
+#[derive(Debug, Clone, Default, JsonSchema, Deserialize)]
+pub struct ErrorConfig {
+    /// Format of the error bodies
+    #[serde(default)]
+    pub format: ErrorFormat,
+    /// Send internal error messages to the client, true unless the env is prod
+    pub expose_details: Option<bool>,
+    /// Prefix of the problem `type` uri, `about:blank` is used if not set
+    pub type_base: Option<String>,
+}
+
+/// Format of the error bodies
+#[derive(Debug, Default, Clone, Copy, JsonSchema, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorFormat {
+    /// RFC 7807 `application/problem+json`
+    #[default]
+    Problem,
+    /// Plain text message
+    Plain,
+}
+
+/// `[web.envelope]`, wrapping the responses into `{code, message, data, success}`
+#[derive(Debug, Clone, JsonSchema, Deserialize)]
+pub struct EnvelopeConfig {
+    /// toggle enable
+    #[serde(default)]
+    pub enable: bool,
+    /// Code of the successful responses
+    #[serde(default)]
+    pub success_code: i64,
+    /// Message of the successful responses
+    #[serde(default = "default_success_message")]
+    pub success_message: String,
+    /// Names of the envelope fields
+    #[serde(default)]
+    pub fields: EnvelopeFields,
+    /// Codes of the errors by http status, such as `404 = 1004`, the status is the code otherwise
+    #[serde(default, deserialize_with = "deserialize_codes")]
+    #[schemars(with = "HashMap<String, i64>")]
+    pub codes: HashMap<u16, i64>,
+    /// Bodies larger than this, or of unknown size such as streams, are sent without the envelope
+    #[serde(default = "default_max_body_size")]
+    pub max_body_size: usize,
+}
+
+impl Default for EnvelopeConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            success_code: 0,
+            success_message: default_success_message(),
+            fields: EnvelopeFields::default(),
+            codes: HashMap::new(),
+            max_body_size: default_max_body_size(),
+        }
+    }
+}
+
+/// Names of the envelope fields, an empty name leaves the field out
+#[derive(Debug, Clone, JsonSchema, Deserialize)]
+pub struct EnvelopeFields {
+    /// Business code
+    #[serde(default = "default_code_field")]
+    pub code: String,
+    /// Message
+    #[serde(default = "default_message_field")]
+    pub message: String,
+    /// Body of the response
+    #[serde(default = "default_data_field")]
+    pub data: String,
+    /// Whether the request succeeded
+    #[serde(default = "default_success_field")]
+    pub success: String,
+}
+
+impl Default for EnvelopeFields {
+    fn default() -> Self {
+        Self {
+            code: default_code_field(),
+            message: default_message_field(),
+            data: default_data_field(),
+            success: default_success_field(),
+        }
+    }
+}
+
+fn default_success_message() -> String {
+    "ok".to_string()
+}
+
+fn default_max_body_size() -> usize {
+    1024 * 1024
+}
+
+/// The keys of `[web.envelope.codes]` are http status codes
+fn deserialize_codes<'de, D>(deserializer: D) -> Result<HashMap<u16, i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    HashMap::<String, i64>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(status, code)| {
+            match status.parse::<u16>().ok().filter(|s| axum::http::StatusCode::from_u16(*s).is_ok()) {
+                Some(status) => Ok((status, code)),
+                None => Err(serde::de::Error::custom(format!(
+                    "[web.envelope.codes] key `{status}` is not a http status"
+                ))),
+            }
+        })
+        .collect()
+}
+
+fn default_code_field() -> String {
+    "code".to_string()
+}
+
+fn default_message_field() -> String {
+    "message".to_string()
+}
+
+fn default_data_field() -> String {
+    "data".to_string()
+}
+
+fn default_success_field() -> String {
+    "success".to_string()
+}
+
 /// Server middleware configuration structure.
 #[derive(Debug, Clone, JsonSchema, Deserialize)]
 pub struct Middlewares {
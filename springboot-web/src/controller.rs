@@ -0,0 +1,73 @@
+//! Controllers group handlers as methods of a struct built by dependency injection:
+//!
+//! ```ignore
+//! #[controller("/post")]
+//! struct PostController {
+//!     #[inject(component)]
+//!     svc: BlogService,
+//! }
+//!
+//! #[controller]
+//! impl PostController {
+//!     #[get("/{id}")]
+//!     async fn get(&self, Path(id): Path<String>) -> Result<Json<BlogPost>> {
+//!         Ok(Json(self.svc.get(&id).await?))
+//!     }
+//! }
+//! ```
+//!
+//! The controller is installed as a service, each request borrows it from the current app as a `ComponentRef`.
+use crate::error::WebError;
+use springboot::application::App;
+use springboot::component::component::ComponentRef;
+use springboot::component::ComponentRegistry;
+use springboot::error::AppError;
+
+/// A struct annotated with `#[controller("/prefix")]`
+pub trait Controller: Clone + Send + Sync + 'static {
+    /// Prefix of the paths of the handler methods
+    const PREFIX: &'static str;
+}
+
+/// Length of the path of a handler method, prefixed by its controller
+#[doc(hidden)]
+pub const fn path_len<C: Controller>(path: &str) -> usize {
+    C::PREFIX.len() + path.len()
+}
+
+/// Path of a handler method prefixed by its controller, computed in a const by the route macros
+#[doc(hidden)]
+pub const fn path_bytes<C: Controller, const N: usize>(path: &str) -> [u8; N] {
+    let (prefix, path) = (C::PREFIX.as_bytes(), path.as_bytes());
+    let mut bytes = [0; N];
+    let mut i = 0;
+    while i < prefix.len() {
+        bytes[i] = prefix[i];
+        i += 1;
+    }
+    while i < N {
+        bytes[i] = path[i - prefix.len()];
+        i += 1;
+    }
+    bytes
+}
+
+/// The path of [path_bytes] as a string
+#[doc(hidden)]
+pub const fn path_str(bytes: &'static [u8]) -> &'static str {
+    match std::str::from_utf8(bytes) {
+        Ok(path) => path,
+        Err(_) => panic!("the prefixed path is not utf-8"),
+    }
+}
+
+/// The controller of the current app, an error if it is not installed
+#[doc(hidden)]
+pub fn resolve<C: Controller>() -> Result<ComponentRef<C>, WebError> {
+    App::try_current()
+        .and_then(|app| {
+            app.get_component_ref::<C>()
+                .ok_or(AppError::ComponentNotExist(std::any::type_name::<C>()))
+        })
+        .map_err(|e| WebError::from(anyhow::Error::from(e)))
+}
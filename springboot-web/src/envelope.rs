@@ -0,0 +1,270 @@
+//! Unified response envelope configured under `[web.envelope]`:
+//!
+//! ```toml
+//! [web.envelope]
+//! enable = true
+//! success_code = 0
+//! fields = { code = "code", message = "msg", data = "data", success = "" }
+//!
+//! [web.envelope.codes]
+//! 404 = 1004
+//! ```
+//!
+//! Successful json and text responses become the `data` of the envelope, errors keep their http status.
+//! Handlers annotated with `#[raw_response]` are sent as is, so are streams, invalid json
+//! and bodies larger than `max_body_size` (1 MiB by default).
+use crate::config::{EnvelopeConfig, EnvelopeFields};
+use crate::problem::Problem;
+use axum::body::{Body, HttpBody};
+use axum::extract::Request;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Marks a response to be sent without the envelope
+#[derive(Debug, Clone, Copy)]
+pub struct RawResponse;
+
+/// Code generated by `#[raw_response]`
+#[doc(hidden)]
+pub async fn mark_raw(mut response: Response) -> Response {
+    response.extensions_mut().insert(RawResponse);
+    response
+}
+
+/// `[web.envelope]` applied by the web starter when enabled
+#[derive(Debug, Clone)]
+pub struct Envelope {
+    success_code: i64,
+    success_message: String,
+    fields: EnvelopeFields,
+    codes: HashMap<u16, i64>,
+    max_body_size: usize,
+}
+
+impl Envelope {
+    /// Resolve the config
+    pub fn new(config: &EnvelopeConfig) -> Self {
+        Self {
+            success_code: config.success_code,
+            success_message: config.success_message.clone(),
+            fields: config.fields.clone(),
+            codes: config.codes.clone(),
+            max_body_size: config.max_body_size,
+        }
+    }
+
+    /// Code of an error with the http status, from `[web.envelope.codes]` or the status itself
+    pub fn code_of(&self, status: StatusCode) -> i64 {
+        self.codes.get(&status.as_u16()).copied().unwrap_or(status.as_u16() as i64)
+    }
+
+    /// Envelope of a successful response
+    pub fn success(&self, data: Value) -> Value {
+        self.body(self.success_code, self.success_message.clone(), data, true)
+    }
+
+    /// Envelope of an error, the code is looked up by status if not given
+    pub fn failure(&self, status: StatusCode, code: Option<i64>, message: String, data: Option<Value>) -> Value {
+        let code = code.unwrap_or_else(|| self.code_of(status));
+        self.body(code, message, data.unwrap_or(Value::Null), false)
+    }
+
+    fn body(&self, code: i64, message: String, data: Value, success: bool) -> Value {
+        let mut body = Map::new();
+        let fields = [
+            (&self.fields.code, Value::from(code)),
+            (&self.fields.message, Value::from(message)),
+            (&self.fields.data, data),
+            (&self.fields.success, Value::from(success)),
+        ];
+        for (name, value) in fields {
+            if !name.is_empty() {
+                body.insert(name.clone(), value);
+            }
+        }
+        Value::Object(body)
+    }
+
+    fn respond(status: StatusCode, body: Value) -> Response {
+        let mut response = (status, axum::Json(body)).into_response();
+        response.extensions_mut().insert(RawResponse);
+        response
+    }
+}
+
+fn content_type(response: &Response) -> &str {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+}
+
+/// Wrap the json and text bodies of the successes and the text bodies of the errors
+pub(crate) async fn wrap(envelope: Envelope, req: Request, next: Next) -> Response {
+    let mut response = next.run(req).await;
+    if response.extensions().get::<RawResponse>().is_some() {
+        return response;
+    }
+    let status = response.status();
+    if let Some(problem) = response.extensions_mut().remove::<Problem>() {
+        let data = (!problem.extensions.is_empty()).then_some(Value::Object(problem.extensions));
+        let message = problem.detail.unwrap_or(problem.title);
+        let mut envelope = Envelope::respond(status, envelope.failure(status, problem.code, message, data));
+        envelope.headers_mut().extend(
+            response
+                .headers()
+                .iter()
+                .filter(|(name, _)| *name != header::CONTENT_TYPE && *name != header::CONTENT_LENGTH)
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+        return envelope;
+    }
+    let is_json = content_type(&response).starts_with("application/json");
+    let is_text = content_type(&response).starts_with("text/plain");
+    let wrapped = if status.is_success() {
+        is_json || is_text
+    } else {
+        (status.is_client_error() || status.is_server_error()) && is_text
+    };
+    // a stream is not buffered, nor is a body too large to be held in memory
+    let size = response.body().size_hint().upper();
+    if !wrapped || size.is_none_or(|size| size > envelope.max_body_size as u64) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, envelope.max_body_size).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("read response body failed:{:?}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let data = if is_json {
+        match serde_json::from_slice(&bytes) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("response is not valid json, it is sent without the envelope:{:?}", e);
+                return Response::from_parts(parts, Body::from(bytes));
+            }
+        }
+    } else {
+        Value::from(String::from_utf8_lossy(&bytes).into_owned())
+    };
+    let body = if status.is_success() {
+        envelope.success(data)
+    } else {
+        let message = data.as_str().unwrap_or_default().to_string();
+        envelope.failure(status, None, message, None)
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    let body = serde_json::to_vec(&body).expect("envelope is always serializable");
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{mark_raw, wrap, Envelope};
+    use crate::config::EnvelopeConfig;
+    use crate::error::{KnownWebError, WebError};
+    use crate::Router;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_envelope() {
+        let mut config = EnvelopeConfig::default();
+        config.fields.message = "msg".to_string();
+        config.fields.success = String::new();
+        config.codes.insert(404, 1004);
+        config.max_body_size = 1024;
+        let envelope = Envelope::new(&config);
+
+        let router = Router::new()
+            .route("/post", get(|| async { axum::Json(serde_json::json!({ "title": "hi" })) }))
+            .route("/hello", get(|| async { "hello" }))
+            .route(
+                "/missing",
+                get(|| async { Err::<(), _>(WebError::from(KnownWebError::not_found("post not found"))) }),
+            )
+            .route(
+                "/teapot",
+                get(|| async { Err::<(), _>(WebError::from(KnownWebError::im_a_teapot("short").with_code(7))) }),
+            )
+            .route(
+                "/raw",
+                get(|| async { "raw" }).layer(axum::middleware::map_response(mark_raw)),
+            )
+            .route(
+                "/broken",
+                get(|| async { ([(axum::http::header::CONTENT_TYPE, "application/json")], "{\"title\":") }),
+            )
+            .route("/large", get(|| async { "x".repeat(2048) }))
+            .route(
+                "/stream",
+                get(|| async {
+                    let chunks = futures::stream::iter([Ok::<_, std::io::Error>("a"), Ok("b")]);
+                    ([(axum::http::header::CONTENT_TYPE, "text/plain")], Body::from_stream(chunks))
+                }),
+            )
+            .layer(axum::middleware::from_fn(move |req, next| wrap(envelope.clone(), req, next)));
+        let call = |uri: &'static str| {
+            let router = router.clone();
+            async move {
+                let req = Request::get(uri).body(Body::empty()).unwrap();
+                let response = router.oneshot(req).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+
+        let json = |s: &str| serde_json::from_str::<serde_json::Value>(s).unwrap();
+        let (status, body) = call("/post").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json(&body), serde_json::json!({ "code": 0, "msg": "ok", "data": { "title": "hi" } }));
+        let (status, body) = call("/hello").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json(&body), serde_json::json!({ "code": 0, "msg": "ok", "data": "hello" }));
+        let (status, body) = call("/missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(json(&body), serde_json::json!({ "code": 1004, "msg": "post not found", "data": null }));
+        let (status, body) = call("/teapot").await;
+        assert_eq!(status, StatusCode::IM_A_TEAPOT);
+        assert_eq!(json(&body), serde_json::json!({ "code": 7, "msg": "short", "data": null }));
+        assert_eq!(call("/raw").await, (StatusCode::OK, "raw".to_string()));
+        assert_eq!(call("/broken").await, (StatusCode::OK, "{\"title\":".to_string()));
+        assert_eq!(call("/large").await, (StatusCode::OK, "x".repeat(2048)));
+        assert_eq!(call("/stream").await, (StatusCode::OK, "ab".to_string()));
+    }
+
+    #[test]
+    fn test_codes() {
+        use crate::config::WebConfig;
+        use springboot::config::ConfigRegistry;
+        use springboot::testing::TestApp;
+
+        let config = TestApp::builder("[web.envelope.codes]\n404 = 1004").get_config::<WebConfig>().unwrap();
+        assert_eq!(config.envelope.codes.get(&404), Some(&1004));
+        let err = TestApp::builder("[web.envelope.codes]\nmissing = 1004").get_config::<WebConfig>().unwrap_err();
+        assert!(err.to_string().contains("key `missing` is not a http status"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_codes_fail_build() {
+        use crate::WebStarter;
+        use springboot::testing::TestApp;
+
+        let result = TestApp::build(TestApp::builder("[web.envelope.codes]\nmissing = 1004").add_starter(WebStarter)).await;
+        assert!(result.is_err());
+    }
+}
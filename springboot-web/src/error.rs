@@ -1,10 +1,14 @@
 #![allow(missing_docs)]
+use crate::exception::handle_exception;
+use crate::problem::{ErrorSettings, Problem, RequestContext};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use serde::Serialize;
 use springboot::error::AppError;
 use thiserror::Error;
+use validator::{ValidationErrors, ValidationErrorsKind};
 
 pub type Result<T> = std::result::Result<T, WebError>;
 
@@ -14,6 +18,23 @@ pub type Result<T> = std::result::Result<T, WebError>;
 pub struct KnownWebError {
     status_code: StatusCode,
     msg: String,
+    extensions: serde_json::Map<String, serde_json::Value>,
+    code: Option<i64>,
+}
+
+impl KnownWebError {
+    /// Attach an extension member to the problem details of the error
+    pub fn with_extension<V: Serialize>(mut self, key: impl Into<String>, value: V) -> Self {
+        let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+        self.extensions.insert(key.into(), value);
+        self
+    }
+
+    /// Code of the response envelope, overriding `[web.envelope.codes]`
+    pub fn with_code(mut self, code: i64) -> Self {
+        self.code = Some(code);
+        self
+    }
 }
 
 macro_rules! impl_known_status_error {
@@ -28,6 +49,8 @@ macro_rules! impl_known_status_error {
                 Self {
                     status_code,
                     msg: msg.into(),
+                    extensions: Default::default(),
+                    code: None,
                 }
             }
         $(
@@ -235,13 +258,60 @@ impl_known_status_error!(
 
 );
 
+/// A failed validation of a field, `field` is the path such as `author.name` or `tags[0]`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Flatten the nested errors of [ValidationErrors], sorted by field
+pub fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    fn collect(prefix: &str, errors: &ValidationErrors, out: &mut Vec<FieldError>) {
+        for (field, kind) in errors.errors() {
+            let path = if prefix.is_empty() {
+                field.to_string()
+            } else {
+                format!("{prefix}.{field}")
+            };
+            match kind {
+                ValidationErrorsKind::Field(errors) => out.extend(errors.iter().map(|e| FieldError {
+                    field: path.clone(),
+                    code: e.code.to_string(),
+                    message: e.message.as_ref().map(|m| m.to_string()),
+                })),
+                ValidationErrorsKind::Struct(errors) => collect(&path, errors, out),
+                ValidationErrorsKind::List(items) => {
+                    for (index, errors) in items {
+                        collect(&format!("{path}[{index}]"), errors, out);
+                    }
+                }
+            }
+        }
+    }
+    let mut out = vec![];
+    collect("", errors, &mut out);
+    out.sort_by(|a, b| a.field.cmp(&b.field));
+    out
+}
+
 #[derive(Error, Debug)]
 pub enum WebError {
     #[error(transparent)]
     ResponseStatusError(#[from] KnownWebError),
 
+    /// the request could not be extracted, such as malformed json or a missing query parameter
+    #[error("invalid request, status code is {0}: {1}")]
+    RejectionErr(StatusCode, String),
+
+    /// the extracted value failed its validation
+    #[error("validation failed: {0}")]
+    ValidationErr(#[from] ValidationErrors),
+
     #[error("get server config failed for typeof {0}, {1}")]
-    ConfigDeserializeErr(&'static str, AppError),
+    ConfigDeserializeErr(&'static str, Box<AppError>),
 
     #[error(transparent)]
     ServerError(#[from] anyhow::Error),
@@ -250,19 +320,43 @@ pub enum WebError {
 // Tell axum how to convert `AppError` into a response.
 impl IntoResponse for WebError {
     fn into_response(self) -> Response {
+        let trace_id = RequestContext::current().map(|c| c.trace_id).unwrap_or_default();
         match self {
             Self::ResponseStatusError(e) => {
-                tracing::warn!("handler error:{:?}", e);
-                (e.status_code, e.msg)
+                tracing::warn!("handler error[{}]:{:?}", trace_id, e);
+                let mut problem = Problem::new(e.status_code, Some(e.msg)).with_extensions(e.extensions);
+                problem.code = e.code;
+                problem
+            }
+            Self::RejectionErr(status, message) => {
+                tracing::warn!("request rejected[{}]:{}", trace_id, message);
+                Problem::new(status, Some(message))
+            }
+            Self::ValidationErr(errors) => {
+                let mut extensions = serde_json::Map::new();
+                extensions.insert("errors".to_string(), serde_json::json!(field_errors(&errors)));
+                Problem::new(StatusCode::UNPROCESSABLE_ENTITY, Some("validation failed".to_string()))
+                    .with_extensions(extensions)
+            }
+            Self::ServerError(e) => {
+                if let Some(response) = handle_exception(&e) {
+                    tracing::warn!("handled error[{}]:{:?}", trace_id, e);
+                    return response;
+                }
+                tracing::error!("internal server error[{}]:{:?}", trace_id, e);
+                let detail = ErrorSettings::current()
+                    .expose_details
+                    .then(|| format!("Something went wrong: {}", e));
+                Problem::new(StatusCode::INTERNAL_SERVER_ERROR, detail)
             }
             _other => {
-                tracing::error!("internal server error:{:?}", _other);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Something went wrong: {}", _other),
-                )
+                tracing::error!("internal server error[{}]:{:?}", trace_id, _other);
+                let detail = ErrorSettings::current()
+                    .expose_details
+                    .then(|| format!("Something went wrong: {}", _other));
+                Problem::new(StatusCode::INTERNAL_SERVER_ERROR, detail)
             }
         }
-            .into_response()
+        .into_response()
     }
 }
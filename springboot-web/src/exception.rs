@@ -0,0 +1,106 @@
+use axum::response::Response;
+pub use inventory::submit;
+use std::collections::HashMap;
+
+/// Maps an error type to a response, generated by `#[exception_handler(ErrorType)]`
+pub trait ExceptionHandler: Send + Sync + 'static {
+    /// Name of the handler
+    fn name(&self) -> &'static str;
+
+    /// Type name of the handled error
+    fn error_type(&self) -> &'static str;
+
+    /// The response of the error, `None` if the error is not of the handled type
+    fn handle(&self, error: &anyhow::Error) -> Option<Response>;
+}
+
+inventory::collect!(&'static dyn ExceptionHandler);
+
+/// auto_config
+#[macro_export]
+macro_rules! submit_exception_handler {
+    ($ty:ident) => {
+        ::springboot_web::exception::submit! {
+            &$ty as &dyn ::springboot_web::exception::ExceptionHandler
+        }
+    };
+}
+
+/// Response of the first exception handler accepting the error
+pub fn handle_exception(error: &anyhow::Error) -> Option<Response> {
+    inventory::iter::<&dyn ExceptionHandler>
+        .into_iter()
+        .find_map(|handler| handler.handle(error))
+}
+
+/// Check that no two exception handlers handle the same error type,
+/// [handle_exception] would pick one of them arbitrarily
+pub fn check_exception_handlers() -> anyhow::Result<()> {
+    let mut handlers = HashMap::new();
+    let duplicates: Vec<String> = inventory::iter::<&dyn ExceptionHandler>
+        .into_iter()
+        .filter_map(|handler| {
+            let other = handlers.insert(handler.error_type(), handler.name())?;
+            Some(format!(
+                "duplicate exception handler for `{}`, handled by both `{}` and `{}`",
+                handler.error_type(),
+                other,
+                handler.name()
+            ))
+        })
+        .collect();
+    if !duplicates.is_empty() {
+        anyhow::bail!("{}", duplicates.join(", "));
+    }
+    Ok(())
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{handle_exception, ExceptionHandler};
+    use crate::error::WebError;
+    use anyhow::Context;
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("post {0} not found")]
+    struct PostNotFound(u64);
+
+    struct PostNotFoundHandler;
+
+    impl ExceptionHandler for PostNotFoundHandler {
+        fn name(&self) -> &'static str {
+            "PostNotFoundHandler"
+        }
+
+        fn error_type(&self) -> &'static str {
+            std::any::type_name::<PostNotFound>()
+        }
+
+        fn handle(&self, error: &anyhow::Error) -> Option<Response> {
+            let error = error.downcast_ref::<PostNotFound>()?;
+            Some((StatusCode::NOT_FOUND, error.to_string()).into_response())
+        }
+    }
+
+    inventory::submit! {
+        &PostNotFoundHandler as &dyn ExceptionHandler
+    }
+
+    #[tokio::test]
+    async fn test_handle_exception() {
+        let error = anyhow::Error::new(PostNotFound(7)).context("load post");
+        let response = handle_exception(&error).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = WebError::from(anyhow::Error::new(PostNotFound(7))).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, "post 7 not found");
+
+        assert!(handle_exception(&anyhow::anyhow!("other")).is_none());
+        let response = WebError::from(anyhow::anyhow!("other")).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
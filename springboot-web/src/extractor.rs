@@ -4,12 +4,34 @@ use crate::error::{Result, WebError};
 use crate::AppState;
 use anyhow::Context;
 use axum::http::request::Parts;
+use springboot::component::component::ComponentRef;
 use springboot::component::ComponentRegistry;
 use springboot::config::{ConfigRegistry, Configurable};
+use springboot::application::AppBuilder;
+use std::any::Any;
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::result::Result as StdResult;
 
+/// Components and configs of the app, used by the extractors
+#[allow(clippy::result_large_err)]
+impl AppState {
+    /// get Component
+    pub fn get_component<T: Clone + Send + Sync + 'static>(&self) -> Result<T> {
+        Ok(self.app.try_get_component().context("get_component failed")?)
+    }
+
+    /// get Config
+    pub fn get_config<T: serde::de::DeserializeOwned + Configurable>(&self) -> Result<T> {
+        self.app
+            .get_config::<T>()
+            .map_err(|e| WebError::ConfigDeserializeErr(std::any::type_name::<T>(), Box::new(e)))
+    }
+}
+
 /// Extending the functionality of RequestParts
+#[deprecated(note = "extract `State<AppState>` or use the `FromRef` of the router state instead")]
+#[allow(clippy::result_large_err)]
 pub trait RequestPartsExt {
     /// get AppState
     fn get_app_state(&self) -> &AppState;
@@ -21,6 +43,8 @@ pub trait RequestPartsExt {
     fn get_config<T: serde::de::DeserializeOwned + Configurable>(&self) -> Result<T>;
 }
 
+// the web starter still puts the AppState into the request extensions for this trait
+#[allow(deprecated)]
 impl RequestPartsExt for Parts {
     fn get_app_state(&self) -> &AppState {
         self.extensions
@@ -29,18 +53,11 @@ impl RequestPartsExt for Parts {
     }
 
     fn get_component<T: Clone + Send + Sync + 'static>(&self) -> Result<T> {
-        Ok(self
-            .get_app_state()
-            .app
-            .try_get_component()
-            .context("get_component failed")?)
+        self.get_app_state().get_component()
     }
 
     fn get_config<T: serde::de::DeserializeOwned + Configurable>(&self) -> Result<T> {
-        self.get_app_state()
-            .app
-            .get_config::<T>()
-            .map_err(|e| WebError::ConfigDeserializeErr(std::any::type_name::<T>(), e))
+        self.get_app_state().get_config()
     }
 }
 
@@ -52,11 +69,12 @@ impl<T, S> FromRequestParts<S> for Component<T>
 where
     T: Clone + Send + Sync + 'static,
     S: Send + Sync,
+    AppState: FromRef<S>,
 {
     type Rejection = WebError;
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> StdResult<Self, Self::Rejection> {
-        parts.get_component::<T>().map(|c| Component(c))
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> StdResult<Self, Self::Rejection> {
+        AppState::from_ref(state).get_component::<T>().map(|c| Component(c))
     }
 }
 
@@ -74,6 +92,99 @@ impl<T: Clone> DerefMut for Component<T> {
     }
 }
 
+/// Extract a component of the app by reference, without cloning it.
+///
+/// Named components are extracted with `Inject<Named<N, T>>` and trait objects
+/// registered as `Arc<dyn Trait>` with `Inject<Arc<dyn Trait>>`.
+/// The components injected into the handlers of the route macros are checked when the web server starts.
+pub struct Inject<T>(pub ComponentRef<T>);
+
+impl<T, S> FromRequestParts<S> for Inject<T>
+where
+    T: Any + Send + Sync,
+    S: Send + Sync,
+    AppState: FromRef<S>,
+{
+    type Rejection = WebError;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> StdResult<Self, Self::Rejection> {
+        AppState::from_ref(state)
+            .app
+            .get_component_ref::<T>()
+            .map(Inject)
+            .ok_or_else(|| anyhow::anyhow!("{} component not exists in registry", std::any::type_name::<T>()).into())
+    }
+}
+
+impl<T> Deref for Inject<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A component required by a handler, see [HandlerRegistrar::dependencies](crate::handler::HandlerRegistrar::dependencies)
+#[derive(Debug, Clone, Copy)]
+pub struct Dependency {
+    /// Type name of the component
+    pub type_name: &'static str,
+    exists: fn(&AppBuilder) -> bool,
+}
+
+impl Dependency {
+    /// Dependency on the component of type `T`
+    pub fn of<T: Any + Send + Sync>() -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            exists: |app| app.has_component::<T>(),
+        }
+    }
+
+    /// Whether the app being built has the component
+    pub fn exists(&self, app: &AppBuilder) -> bool {
+        (self.exists)(app)
+    }
+}
+
+/// Finds the dependency of an extractor type in the code generated by the route macros:
+/// `(&DependencyProbe::<E>::new()).dependency()` is `Some` for the injecting extractors only.
+#[doc(hidden)]
+pub struct DependencyProbe<E>(PhantomData<fn() -> E>);
+
+impl<E> DependencyProbe<E> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait InjectDependency {
+    fn dependency(&self) -> Option<Dependency>;
+}
+
+impl<T: Any + Send + Sync> InjectDependency for DependencyProbe<Inject<T>> {
+    fn dependency(&self) -> Option<Dependency> {
+        Some(Dependency::of::<T>())
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> InjectDependency for DependencyProbe<Component<T>> {
+    fn dependency(&self) -> Option<Dependency> {
+        Some(Dependency::of::<T>())
+    }
+}
+
+#[doc(hidden)]
+pub trait NoDependency {
+    fn dependency(&self) -> Option<Dependency> {
+        None
+    }
+}
+
+impl<E> NoDependency for &DependencyProbe<E> {}
+
 pub struct Config<T>(pub T)
 where
     T: serde::de::DeserializeOwned + Configurable;
@@ -83,11 +194,12 @@ impl<T, S> FromRequestParts<S> for Config<T>
 where
     T: serde::de::DeserializeOwned + Configurable,
     S: Send + Sync,
+    AppState: FromRef<S>,
 {
     type Rejection = WebError;
 
-    async fn from_request_parts(parts: &mut Parts, _s: &S) -> StdResult<Self, Self::Rejection> {
-        parts.get_config().map(|c| Config(c))
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> StdResult<Self, Self::Rejection> {
+        AppState::from_ref(state).get_config().map(|c| Config(c))
     }
 }
 
@@ -110,3 +222,241 @@ where
         &mut self.0
     }
 }
+
+/// Validates the extracted value with its [validator::Validate] implementation,
+/// failures are rejected with a 422 listing every field error.
+///
+/// Supports `Valid<Json<T>>`, `Valid<Query<T>>` and `Valid<Form<T>>`.
+pub struct Valid<E>(pub E);
+
+fn rejection(status: axum::http::StatusCode, message: String) -> WebError {
+    WebError::RejectionErr(status, message)
+}
+
+impl<T, S> FromRequest<S> for Valid<Json<T>>
+where
+    T: serde::de::DeserializeOwned + validator::Validate,
+    S: Send + Sync,
+{
+    type Rejection = WebError;
+
+    async fn from_request(req: Request, state: &S) -> StdResult<Self, Self::Rejection> {
+        let Json(value) = <Json<T> as FromRequest<S>>::from_request(req, state)
+            .await
+            .map_err(|r| rejection(r.status(), r.body_text()))?;
+        value.validate()?;
+        Ok(Valid(Json(value)))
+    }
+}
+
+impl<T, S> FromRequest<S> for Valid<Form<T>>
+where
+    T: serde::de::DeserializeOwned + validator::Validate,
+    S: Send + Sync,
+{
+    type Rejection = WebError;
+
+    async fn from_request(req: Request, state: &S) -> StdResult<Self, Self::Rejection> {
+        let Form(value) = <Form<T> as FromRequest<S>>::from_request(req, state)
+            .await
+            .map_err(|r| rejection(r.status(), r.body_text()))?;
+        value.validate()?;
+        Ok(Valid(Form(value)))
+    }
+}
+
+impl<T, S> FromRequestParts<S> for Valid<Query<T>>
+where
+    T: serde::de::DeserializeOwned + validator::Validate,
+    S: Send + Sync,
+{
+    type Rejection = WebError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> StdResult<Self, Self::Rejection> {
+        let Query(value) = <Query<T> as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map_err(|r| rejection(r.status(), r.body_text()))?;
+        value.validate()?;
+        Ok(Valid(Query(value)))
+    }
+}
+
+impl<E> Deref for Valid<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E> DerefMut for Valid<E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{Dependency, Inject, Json, Query, Valid};
+    use crate::{AppState, Router};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::{get, post};
+    use serde::Deserialize;
+    use tower::ServiceExt;
+    use validator::Validate;
+
+    #[tokio::test]
+    async fn test_valid() {
+        #[derive(Deserialize, Validate)]
+        struct Author {
+            #[validate(length(min = 1))]
+            name: String,
+        }
+
+        #[derive(Deserialize, Validate)]
+        struct Post {
+            #[validate(length(min = 1, message = "title must not be empty"))]
+            title: String,
+            #[validate(range(min = 1))]
+            words: u32,
+            #[validate(nested)]
+            author: Author,
+        }
+
+        #[derive(Deserialize, Validate)]
+        struct Page {
+            #[validate(range(max = 100))]
+            size: u32,
+        }
+
+        let router = Router::new()
+            .route("/post", post(|Valid(Json(post)): Valid<Json<Post>>| async move { post.title }))
+            .route("/posts", get(|Valid(Query(page)): Valid<Query<Page>>| async move { page.size.to_string() }));
+        let call = |req: Request<Body>| async {
+            let response = router.clone().oneshot(req).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, String::from_utf8(body.to_vec()).unwrap())
+        };
+        let json = |body: &'static str| {
+            Request::post("/post")
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let (status, body) = call(json(r#"{"title":"hi","words":1,"author":{"name":"a"}}"#)).await;
+        assert_eq!((status, body.as_str()), (StatusCode::OK, "hi"));
+
+        let (status, body) = call(json(r#"{"title":"","words":0,"author":{"name":""}}"#)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let fields: Vec<&str> = body["errors"].as_array().unwrap().iter().map(|e| e["field"].as_str().unwrap()).collect();
+        assert_eq!(fields, ["author.name", "title", "words"]);
+        assert_eq!(body["errors"][1]["message"], "title must not be empty");
+
+        let (status, body) = call(json(r#"{"title":1}"#)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&body).unwrap()["status"], 422);
+
+        let (status, _) = call(json("{")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = call(Request::get("/posts?size=101").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (status, body) = call(Request::get("/posts?size=10").body(Body::empty()).unwrap()).await;
+        assert_eq!((status, body.as_str()), (StatusCode::OK, "10"));
+    }
+
+    #[tokio::test]
+    async fn test_inject() {
+        use springboot::component::component::Named;
+        use springboot::component::MutableComponentRegistry;
+        use springboot::testing::TestApp;
+        use std::sync::Arc;
+
+        #[derive(Clone)]
+        struct Pool {
+            name: &'static str,
+        }
+        struct Replica;
+        trait Mailer: Send + Sync {
+            fn send(&self) -> &'static str;
+        }
+        struct Smtp;
+        impl Mailer for Smtp {
+            fn send(&self) -> &'static str {
+                "smtp"
+            }
+        }
+
+        let mut builder = TestApp::builder("");
+        builder
+            .add_component(Pool { name: "primary" })
+            .add_component(Named::<Replica, _>::new(Pool { name: "replica" }))
+            .add_component(Arc::new(Smtp) as Arc<dyn Mailer>);
+        assert!(Dependency::of::<Named<Replica, Pool>>().exists(&builder));
+        assert!(!Dependency::of::<Named<Replica, String>>().exists(&builder));
+        let app = TestApp::build(&mut builder).await.unwrap();
+
+        let router = Router::new()
+            .route(
+                "/",
+                get(
+                    |primary: Inject<Pool>, replica: Inject<Named<Replica, Pool>>, mailer: Inject<Arc<dyn Mailer>>| async move {
+                        format!("{} {} {}", primary.name, replica.name, mailer.send())
+                    },
+                ),
+            )
+            .route("/missing", get(|_: Inject<String>| async { "unreachable" }))
+            .with_state(AppState { app: app.app() });
+        let call = |uri: &'static str| {
+            let router = router.clone();
+            async move {
+                let response = router.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+        assert_eq!(call("/").await, (StatusCode::OK, "primary replica smtp".to_string()));
+        assert_eq!(call("/missing").await.0, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_custom_state() {
+        use super::{Component, FromRef, State};
+        use springboot::component::MutableComponentRegistry;
+        use springboot::testing::TestApp;
+
+        #[derive(Clone)]
+        struct BlogState {
+            app: AppState,
+            title: &'static str,
+        }
+
+        impl FromRef<BlogState> for AppState {
+            fn from_ref(state: &BlogState) -> Self {
+                state.app.clone()
+            }
+        }
+
+        let app = TestApp::build(TestApp::builder("").add_component(7u32)).await.unwrap();
+        let state = BlogState {
+            app: AppState { app: app.app() },
+            title: "blog",
+        };
+        let router = Router::<BlogState>::new()
+            .route(
+                "/",
+                get(|State(state): State<BlogState>, Component(n): Component<u32>| async move {
+                    format!("{} #{n}", state.title)
+                }),
+            )
+            .with_state(state);
+        let response = router.oneshot(Request::get("/").body(Body::empty()).unwrap()).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, "blog #7");
+    }
+}
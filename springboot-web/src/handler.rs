@@ -1,13 +1,47 @@
+use crate::extractor::Dependency;
+use crate::openapi::Operation;
 use crate::Router;
 pub use inventory::submit;
+use springboot::application::AppBuilder;
+use std::collections::HashMap;
 
 pub trait HandlerRegistrar: Send + Sync + 'static {
     /// 注册处理方法到路由, Router::new().route("create", get(create_user));
     fn register(&self, router: Router) -> Router;
+
+    /// OpenAPI operations of the handler
+    fn operations(&self) -> Vec<Operation> {
+        vec![]
+    }
+
+    /// Paths answered for every method, such as the fallback of a scope
+    fn fallback_paths(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// Components injected by the extractors of the handler
+    fn dependencies(&self) -> Vec<Dependency> {
+        vec![]
+    }
+
+    /// Name of the handler, shown when a dependency is missing
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 inventory::collect!(&'static dyn HandlerRegistrar);
 
+/// A handler of a `#[scope]`, registered by the scope rather than by [auto_router]
+#[doc(hidden)]
+pub struct ScopedHandler {
+    /// Type name of the registrar of the scope
+    pub scope: fn() -> &'static str,
+    pub handler: &'static dyn HandlerRegistrar,
+}
+
+inventory::collect!(ScopedHandler);
+
 /// auto_config
 #[macro_export]
 macro_rules! submit_typed_handler {
@@ -18,11 +52,156 @@ macro_rules! submit_typed_handler {
     };
 }
 
+/// Register a handler of a `#[scope]`, used by the route macros
+#[macro_export]
+macro_rules! submit_scoped_handler {
+    ($ty:ident, $scope:path) => {
+        ::springboot_web::handler::submit! {
+            ::springboot_web::handler::ScopedHandler {
+                scope: ::std::any::type_name::<$scope>,
+                handler: &$ty,
+            }
+        }
+    };
+}
+
+/// The handlers of the scope with the given registrar, used by `#[scope]`
+#[doc(hidden)]
+pub fn scoped_handlers(scope: &str) -> impl Iterator<Item = &'static dyn HandlerRegistrar> + '_ {
+    inventory::iter::<ScopedHandler>
+        .into_iter()
+        .filter(move |scoped| (scoped.scope)() == scope)
+        .map(|scoped| scoped.handler)
+}
+
+/// The handler and the handlers of its scope, recursively
+fn with_scoped(handler: &'static dyn HandlerRegistrar) -> Vec<&'static dyn HandlerRegistrar> {
+    let mut handlers = vec![handler];
+    for scoped in scoped_handlers(handler.name()) {
+        handlers.extend(with_scoped(scoped));
+    }
+    handlers
+}
+
+/// All the handlers linked into the binary, including the ones registered by their scopes
+pub fn handlers() -> Vec<&'static dyn HandlerRegistrar> {
+    inventory::iter::<&dyn HandlerRegistrar>
+        .into_iter()
+        .flat_map(|handler| with_scoped(*handler))
+        .collect()
+}
+
 // auto_config
+/// Router of the handlers linked into the binary.
+///
+/// The macros reject two handlers of the same route within a scope at compile time,
+/// the duplicates across top level scopes and controllers are only known here:
+/// the later handler, or scope, is left out and [check_routes] fails the app at startup.
 pub fn auto_router() -> Router {
     let mut router = Router::new();
+    let mut routes = RouteTable::new();
     for handler in inventory::iter::<&dyn HandlerRegistrar> {
+        // a scope is registered with all its handlers
+        let mut added = routes.clone();
+        if let Some(e) = with_scoped(*handler)
+            .into_iter()
+            .find_map(|handler| add_routes(&mut added, handler).err())
+        {
+            tracing::error!("{e}, `{}` is not registered", handler.name());
+            continue;
+        }
+        routes = added;
         router = handler.register(router);
     }
     router
 }
+
+/// Check that no two handlers have the same method and path
+pub fn check_routes() -> anyhow::Result<()> {
+    let mut routes = RouteTable::new();
+    let duplicates: Vec<String> = handlers()
+        .into_iter()
+        .filter_map(|handler| add_routes(&mut routes, handler).err())
+        .collect();
+    if !duplicates.is_empty() {
+        anyhow::bail!("{}", duplicates.join(", "));
+    }
+    Ok(())
+}
+
+/// The registered routes, by method and path without parameter names
+type RouteTable = HashMap<(&'static str, String), &'static str>;
+
+/// The method of the fallback paths, it overlaps every method
+const ANY: &str = "any";
+
+fn add_routes(routes: &mut RouteTable, handler: &dyn HandlerRegistrar) -> Result<(), String> {
+    let mut added: Vec<(&'static str, &'static str, &'static str)> = handler
+        .operations()
+        .into_iter()
+        .map(|operation| (operation.method, operation.path, operation.operation_id))
+        .collect();
+    added.extend(handler.fallback_paths().into_iter().map(|path| (ANY, path, handler.name())));
+    for (method, path, name) in &added {
+        let key = route_key(path);
+        let other = routes
+            .iter()
+            .find(|((m, p), _)| *p == key && (m == method || *m == ANY || *method == ANY));
+        if let Some((_, other)) = other {
+            return Err(format!(
+                "duplicate route `{} {}`, handled by both `{}` and `{}`",
+                method.to_uppercase(),
+                path,
+                other,
+                name
+            ));
+        }
+    }
+    for (method, path, name) in added {
+        routes.insert((method, route_key(path)), name);
+    }
+    Ok(())
+}
+
+/// The path with unnamed parameters, `/post/{id}` and `/post/{pid}` are the same route
+fn route_key(path: &str) -> String {
+    let mut key = String::with_capacity(path.len());
+    let mut in_param = false;
+    for c in path.chars() {
+        match c {
+            '{' => {
+                in_param = true;
+                key.push(c);
+            }
+            '}' => {
+                in_param = false;
+                key.push(c);
+            }
+            '*' if in_param => key.push(c),
+            _ if in_param => {}
+            _ => key.push(c),
+        }
+    }
+    key
+}
+
+/// Check that the app has the components injected into the handlers,
+/// every missing component is reported.
+///
+/// [WebStarter](crate::WebStarter) runs it once the services are installed, so the app fails to start.
+pub fn check_dependencies(app: &AppBuilder) -> anyhow::Result<()> {
+    let missing: Vec<String> = handlers()
+        .into_iter()
+        .flat_map(|handler| {
+            handler
+                .dependencies()
+                .into_iter()
+                .filter(|dependency| !dependency.exists(app))
+                .map(|dependency| format!("`{}` required by `{}`", dependency.type_name, handler.name()))
+        })
+        .collect();
+    if !missing.is_empty() {
+        anyhow::bail!("missing components of web handlers: {}", missing.join(", "));
+    }
+    Ok(())
+}
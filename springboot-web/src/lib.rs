@@ -1,22 +1,37 @@
 pub mod config;
+/// Handlers as methods of structs built by dependency injection
+pub mod controller;
+/// Unified response envelope
+pub mod envelope;
 /// springboot-web defined error
 pub mod error;
+/// Exception handlers mapping error types to responses
+pub mod exception;
 /// axum extract
 pub mod extractor;
 /// axum route handler
 pub mod handler;
 pub mod middleware;
+/// OpenAPI document of the route macros
+pub mod openapi;
+/// RFC 7807 problem details of the errors
+pub mod problem;
 
+pub use anyhow;
 pub use axum;
+pub use validator;
 pub use springboot::async_trait;
 /////////////////web-macros/////////////////////
+pub use springboot_macros::controller;
 pub use springboot_macros::delete;
+pub use springboot_macros::exception_handler;
 pub use springboot_macros::get;
 pub use springboot_macros::head;
 pub use springboot_macros::options;
 pub use springboot_macros::patch;
 pub use springboot_macros::post;
 pub use springboot_macros::put;
+pub use springboot_macros::raw_response;
 /// To use these Procedural Macros, you need to add `springboot-web` dependency
 pub use springboot_macros::route;
 pub use springboot_macros::routes;
@@ -28,16 +43,15 @@ pub use axum::routing::MethodFilter;
 /// MethodRouter with AppState
 pub use axum::routing::MethodRouter;
 /// Router with AppState
-pub use axum::Router;
+pub type Router<S = AppState> = axum::Router<S>;
 
 
 use anyhow::Context;
-use axum::Extension;
+use axum::extract::FromRef;
 use config::ServerConfig;
 use config::WebConfig;
-use springboot::component::component::ComponentRef;
-use springboot::component::ComponentRegistry;
-use springboot::component::MutableComponentRegistry;
+use springboot::component::{ComponentRegistry, MutableComponentRegistry};
+use springboot::info::AppInfo;
 use springboot::{
     application::{App, AppBuilder},
     component::Starter,
@@ -47,28 +61,70 @@ use springboot::{
 use std::{net::SocketAddr, ops::Deref, sync::Arc};
 
 pub type Routers = Vec<Router>;
+
+/// Routers of custom states, the states are built from the [AppState] when the server starts
+#[derive(Clone, Default)]
+pub struct StateRouters(Vec<Arc<dyn Fn(AppState) -> Router + Send + Sync>>);
+
 /// Web Configurator
 pub trait WebConfigurator {
     /// add route to app registry
     fn add_router(&mut self, router: Router) -> &mut Self;
+
+    /// add route of a custom state to app registry, the state embeds the [AppState]
+    /// so the extractors of this crate work through [FromRef]
+    fn add_router_with_state<S, F>(&mut self, router: Router<S>, state: F) -> &mut Self
+    where
+        S: Clone + Send + Sync + 'static,
+        AppState: FromRef<S>,
+        F: Fn(AppState) -> S + Send + Sync + 'static;
 }
 
 impl WebConfigurator for AppBuilder {
     fn add_router(&mut self, router: Router) -> &mut Self {
-        if let Some(routers) = self.get_component_ref::<Routers>() {
-            unsafe {
-                let raw_ptr = ComponentRef::into_raw(routers);
-                let routers = &mut *(raw_ptr as *mut Vec<Router>);
-                routers.push(router);
-            }
-            self
-        } else {
-            self.add_component(vec![router])
-        }
+        self.component_entry::<Routers>().or_default().push(router);
+        self
+    }
+
+    fn add_router_with_state<S, F>(&mut self, router: Router<S>, state: F) -> &mut Self
+    where
+        S: Clone + Send + Sync + 'static,
+        AppState: FromRef<S>,
+        F: Fn(AppState) -> S + Send + Sync + 'static,
+    {
+        let router = move |app_state: AppState| router.clone().with_state(state(app_state));
+        self.component_entry::<StateRouters>().or_default().0.push(Arc::new(router));
+        self
     }
 }
 
-/// State of App
+/// State of App, the state of the routers built by the web starter.
+///
+/// A custom state embeds it and implements [FromRef]:
+/// ```
+/// use springboot_web::axum::extract::{FromRef, State};
+/// use springboot_web::extractor::Component;
+/// use springboot_web::{AppState, Router, WebConfigurator};
+///
+/// #[derive(Clone)]
+/// struct BlogState {
+///     app: AppState,
+///     title: &'static str,
+/// }
+///
+/// impl FromRef<BlogState> for AppState {
+///     fn from_ref(state: &BlogState) -> Self {
+///         state.app.clone()
+///     }
+/// }
+///
+/// async fn index(State(state): State<BlogState>, Component(n): Component<u32>) -> String {
+///     format!("{} #{n}", state.title)
+/// }
+///
+/// let router = Router::new().route("/", springboot_web::axum::routing::get(index));
+/// springboot::App::new().add_router_with_state(router, |app| BlogState { app, title: "blog" });
+/// ```
 #[derive(Clone)]
 pub struct AppState {
     /// App Registry Ref
@@ -76,14 +132,20 @@ pub struct AppState {
 }
 
 /// Web Component Definition
+#[springboot::starter]
 pub struct WebStarter;
 
 #[async_trait]
 impl Starter for WebStarter {
     fn build(&self, app: &mut AppBuilder) {
-        let config = app
-            .get_config::<WebConfig>()
-            .expect("web component config load failed");
+        let config = match app.get_config::<WebConfig>() {
+            Ok(config) => config,
+            Err(e) => {
+                // reported once the services are installed, the app fails to start
+                app.add_startup_check(move |_| Err(e));
+                return;
+            }
+        };
 
         // 1. collect router
         let routers = app.get_component_ref::<Routers>();
@@ -97,19 +159,53 @@ impl Starter for WebStarter {
             }
             None => Router::new(),
         };
-        if let Some(middlewares) = config.middlewares {
-            router = middleware::apply_middleware(router, middlewares);
-        }
-
+        let state_routers = app.get_component::<StateRouters>().unwrap_or_default();
+        let envelope = config
+            .envelope
+            .enable
+            .then(|| envelope::Envelope::new(&config.envelope));
+        let openapi = config.openapi.enable.then(|| {
+            let info = app.get_component::<AppInfo>().unwrap_or_default();
+            let title = config.openapi.title.clone().unwrap_or_else(|| info.name.to_string());
+            let version = config.openapi.version.clone().unwrap_or_else(|| info.version.to_string());
+            let spec = openapi::OpenApi::from_handlers(title, version);
+            openapi::router(&config.openapi, spec)
+        });
+        app.add_component(problem::ErrorSettings::new(&config.error, app.get_env()));
+        app.add_startup_check(|_| Ok(handler::check_routes()?));
+        app.add_startup_check(|_| Ok(exception::check_exception_handlers()?));
+        app.add_startup_check(|app| Ok(handler::check_dependencies(app)?));
+        let middlewares = config.middlewares;
         let server_conf = config.server;
 
-        app.add_scheduler(move |app: Arc<App>| Box::new(Self::schedule(router, app, server_conf)));
+        app.add_scheduler(move |app: Arc<App>| {
+            // the routers of custom states are built with the state of the app
+            let state = AppState { app: app.clone() };
+            for state_router in state_routers.0 {
+                router = router.merge(state_router(state.clone()));
+            }
+            if let Some(envelope) = envelope {
+                router = router.layer(axum::middleware::from_fn(move |req, next| {
+                    envelope::wrap(envelope.clone(), req, next)
+                }));
+            }
+            if let Some(openapi) = openapi {
+                router = router.merge(openapi);
+            }
+            // kept in the extensions for the deprecated `RequestPartsExt`
+            router = router.layer(axum::Extension(state.clone()));
+            let mut router = router.with_state(state);
+            if let Some(middlewares) = middlewares {
+                router = middleware::apply_middleware(router, middlewares);
+            }
+            Box::new(Self::schedule(router, app, server_conf))
+        });
     }
 }
 
 impl WebStarter {
-    async fn schedule(router: Router, app: Arc<App>, config: ServerConfig) -> Result<String> {
-        // 2. bind tcp listener
+    async fn schedule(router: Router<()>, app: Arc<App>, config: ServerConfig) -> Result<String> {
+        // 1. bind tcp listener
         let addr = SocketAddr::from((config.binding, config.port));
         let listener = tokio::net::TcpListener::bind(addr)
             .await
@@ -127,8 +223,16 @@ impl WebStarter {
             tracing::info!("bind tcp listener: http://{addr}" );
         }
 
-        // 3. axum server
-        let router = router.layer(Extension(AppState { app }));
+        // 2. axum server
+        // every request runs in the context of this app, so `App::current` works in handlers
+        let scoped_app = app;
+        let router = router
+            .layer(axum::middleware::from_fn(
+                move |req: axum::extract::Request, next: axum::middleware::Next| {
+                    App::scope(scoped_app.clone(), next.run(req))
+                },
+            ))
+            .layer(axum::middleware::from_fn(problem::request_context));
 
         tracing::info!("axum server started");
         if config.connect_info {
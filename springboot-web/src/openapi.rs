@@ -0,0 +1,422 @@
+//! OpenAPI 3 document built from the operations contributed by the route macros.
+//!
+//! Request and response schemas are inferred from the `Json<T>`, `Path<T>` and `Query<T>`
+//! extractors of the handlers, for the types implementing [JsonSchema].
+//! The document is served when enabled under `[web.openapi]`:
+//!
+//! ```toml
+//! [web.openapi]
+//! enable = true
+//! path = "/openapi.json"
+//! ui = "swagger"
+//! ui_path = "/docs"
+//! # the page loads swagger-ui from unpkg unless the assets are served by the app
+//! ui_assets = "/static/swagger-ui"
+//! ```
+//!
+//! Each operation documents its 200 response, a 500, a 400 when it takes parameters or a body,
+//! and a 422 when an extractor is wrapped in `Valid`.
+use crate::config::{OpenApiConfig, OpenApiUi};
+use crate::handler::handlers;
+use crate::Router;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{InstanceType, ObjectValidation, Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+pub use schemars;
+
+/// An operation contributed by a route macro
+#[derive(Debug, Clone, Copy)]
+pub struct Operation {
+    /// Path template, such as `/post/{id}`
+    pub path: &'static str,
+    /// Lowercase HTTP method
+    pub method: &'static str,
+    /// Name of the handler
+    pub operation_id: &'static str,
+    /// Lines of the doc comment of the handler, the first one is the summary
+    pub docs: &'static [&'static str],
+    /// Schemas of the extractors and the response
+    pub schemas: fn(&mut SchemaGenerator) -> OperationSchemas,
+}
+
+/// Schemas inferred from the handler signature
+#[derive(Debug, Clone, Default)]
+pub struct OperationSchemas {
+    /// `Path<T>`
+    pub path: Option<Schema>,
+    /// `Query<T>`
+    pub query: Option<Schema>,
+    /// `Json<T>` argument
+    pub body: Option<Schema>,
+    /// `Json<T>` return type
+    pub response: Option<Schema>,
+    /// Whether an extractor is wrapped in `Valid`
+    pub validated: bool,
+}
+
+/// Used by the route macros to get the schema of a type if it implements [JsonSchema], see [WithoutSchema]
+#[doc(hidden)]
+pub struct SchemaProbe<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> SchemaProbe<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait WithSchema {
+    fn subschema(&self, gen: &mut SchemaGenerator) -> Option<Schema>;
+    fn inline(&self, gen: &mut SchemaGenerator) -> Option<Schema>;
+}
+
+impl<T: ?Sized + JsonSchema> WithSchema for SchemaProbe<T> {
+    fn subschema(&self, gen: &mut SchemaGenerator) -> Option<Schema> {
+        Some(gen.subschema_for::<T>())
+    }
+
+    fn inline(&self, gen: &mut SchemaGenerator) -> Option<Schema> {
+        Some(T::json_schema(gen))
+    }
+}
+
+/// Picked by method resolution when the type doesn't implement [JsonSchema],
+/// because it takes one more reference than [WithSchema]
+#[doc(hidden)]
+pub trait WithoutSchema {
+    fn subschema(&self, _gen: &mut SchemaGenerator) -> Option<Schema> {
+        None
+    }
+    fn inline(&self, _gen: &mut SchemaGenerator) -> Option<Schema> {
+        None
+    }
+}
+
+impl<T: ?Sized> WithoutSchema for &SchemaProbe<T> {}
+
+/// The OpenAPI document
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenApi {
+    /// Version of the specification
+    pub openapi: &'static str,
+    /// Title and version of the API
+    pub info: Info,
+    /// Operations by path and method
+    pub paths: BTreeMap<String, BTreeMap<&'static str, OperationObject>>,
+    /// Shared schemas
+    pub components: Components,
+}
+
+/// `info` of the document
+#[derive(Debug, Clone, Serialize)]
+pub struct Info {
+    /// Title of the API
+    pub title: String,
+    /// Version of the API
+    pub version: String,
+}
+
+/// `components` of the document
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Components {
+    /// Schemas referenced by the operations
+    pub schemas: BTreeMap<String, Schema>,
+}
+
+/// An operation of the document
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationObject {
+    /// Name of the handler
+    pub operation_id: &'static str,
+    /// First line of the doc comment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// The rest of the doc comment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Path and query parameters
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Parameter>,
+    /// Json request body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Body>,
+    /// Responses by status code
+    pub responses: BTreeMap<&'static str, Response>,
+}
+
+/// A path or query parameter
+#[derive(Debug, Clone, Serialize)]
+pub struct Parameter {
+    /// Name of the parameter
+    pub name: String,
+    /// `path` or `query`
+    #[serde(rename = "in")]
+    pub location: &'static str,
+    /// Whether the parameter must be present
+    pub required: bool,
+    /// Schema of the value
+    pub schema: Schema,
+}
+
+/// A json request or response body
+#[derive(Debug, Clone, Serialize)]
+pub struct Body {
+    /// Whether the body must be present
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    /// Schemas by media type
+    pub content: BTreeMap<&'static str, MediaType>,
+}
+
+/// Schema of a media type
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaType {
+    /// Schema of the body
+    pub schema: Schema,
+}
+
+/// A response of an operation
+#[derive(Debug, Clone, Serialize)]
+pub struct Response {
+    /// Description of the response
+    pub description: &'static str,
+    /// Schemas by media type
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub content: BTreeMap<&'static str, MediaType>,
+}
+
+impl Response {
+    /// The body of an error depends on `[web.error]` and `[web.envelope]`, so it has no schema
+    fn error(description: &'static str) -> Self {
+        Self {
+            description,
+            content: BTreeMap::new(),
+        }
+    }
+}
+
+fn json_content(schema: Schema) -> BTreeMap<&'static str, MediaType> {
+    BTreeMap::from([("application/json", MediaType { schema })])
+}
+
+fn string_schema() -> Schema {
+    Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        ..Default::default()
+    })
+}
+
+/// Properties and required names of an object schema
+fn object(schema: &Schema) -> Option<&ObjectValidation> {
+    match schema {
+        Schema::Object(SchemaObject {
+            object: Some(object), ..
+        }) => Some(object),
+        _ => None,
+    }
+}
+
+/// Names of the `{param}` segments of a path template
+fn path_params(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| name.trim_start_matches('*').to_string())
+        .collect()
+}
+
+impl Operation {
+    fn build(&self, gen: &mut SchemaGenerator) -> OperationObject {
+        let schemas = (self.schemas)(gen);
+        let mut docs = self.docs.iter().map(|line| line.trim());
+        let summary = docs.next().filter(|line| !line.is_empty()).map(String::from);
+        let description = docs.collect::<Vec<_>>().join("\n").trim().to_string();
+
+        let names = path_params(self.path);
+        let path_object = schemas.path.as_ref().and_then(object);
+        let mut parameters: Vec<Parameter> = names
+            .iter()
+            .map(|name| {
+                let schema = match (path_object, &schemas.path) {
+                    (Some(object), _) => object.properties.get(name).cloned(),
+                    (None, Some(schema)) if names.len() == 1 => Some(schema.clone()),
+                    _ => None,
+                };
+                Parameter {
+                    name: name.clone(),
+                    location: "path",
+                    required: true,
+                    schema: schema.unwrap_or_else(string_schema),
+                }
+            })
+            .collect();
+        if let Some(object) = schemas.query.as_ref().and_then(object) {
+            parameters.extend(object.properties.iter().map(|(name, schema)| Parameter {
+                name: name.clone(),
+                location: "query",
+                required: object.required.contains(name),
+                schema: schema.clone(),
+            }));
+        }
+
+        let request_body = schemas.body.map(|schema| Body {
+            required: true,
+            content: json_content(schema),
+        });
+        let mut responses = BTreeMap::from([
+            (
+                "200",
+                Response {
+                    description: "OK",
+                    content: schemas.response.map(json_content).unwrap_or_default(),
+                },
+            ),
+            ("500", Response::error("Internal Server Error")),
+        ]);
+        // the extractors reject what they can't parse
+        if !parameters.is_empty() || request_body.is_some() {
+            responses.insert("400", Response::error("Bad Request"));
+        }
+        if schemas.validated {
+            responses.insert("422", Response::error("Validation failed"));
+        }
+        OperationObject {
+            operation_id: self.operation_id,
+            summary,
+            description: Some(description).filter(|d| !d.is_empty()),
+            parameters,
+            request_body,
+            responses,
+        }
+    }
+}
+
+impl OpenApi {
+    /// Build the document from the given operations
+    pub fn new<'a>(title: impl Into<String>, version: impl Into<String>, operations: impl IntoIterator<Item = &'a Operation>) -> Self {
+        let mut gen = SchemaSettings::openapi3().into_generator();
+        let mut paths: BTreeMap<String, BTreeMap<&'static str, OperationObject>> = BTreeMap::new();
+        for operation in operations {
+            paths
+                .entry(operation.path.to_string())
+                .or_default()
+                .insert(operation.method, operation.build(&mut gen));
+        }
+        Self {
+            openapi: "3.0.3",
+            info: Info {
+                title: title.into(),
+                version: version.into(),
+            },
+            paths,
+            components: Components {
+                schemas: gen.take_definitions().into_iter().collect(),
+            },
+        }
+    }
+
+    /// Build the document from the handlers registered by the route macros
+    pub fn from_handlers(title: impl Into<String>, version: impl Into<String>) -> Self {
+        let operations: Vec<Operation> = handlers()
+            .into_iter()
+            .flat_map(|handler| handler.operations())
+            .collect();
+        Self::new(title, version, &operations)
+    }
+
+    /// Export as json
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("openapi document is always serializable")
+    }
+}
+
+/// Routes serving the document and the ui configured under `[web.openapi]`
+pub(crate) fn router(config: &OpenApiConfig, spec: OpenApi) -> Router {
+    let json = spec.to_json();
+    let mut router = Router::new().route(
+        &config.path,
+        get(move || async move { ([(axum::http::header::CONTENT_TYPE, "application/json")], json).into_response() }),
+    );
+    let assets = |cdn: &str| config.ui_assets.clone().unwrap_or_else(|| cdn.to_string());
+    let page = match config.ui {
+        OpenApiUi::None => return router,
+        OpenApiUi::Swagger => format!(
+            r##"<!doctype html>
+<html>
+<head>
+<title>{title}</title>
+<link rel="stylesheet" href="{assets}/swagger-ui.css">
+</head>
+<body>
+<div id="swagger-ui"></div>
+<script src="{assets}/swagger-ui-bundle.js"></script>
+<script>SwaggerUIBundle({{ url: "{path}", dom_id: "#swagger-ui" }});</script>
+</body>
+</html>"##,
+            title = spec.info.title,
+            assets = assets("https://unpkg.com/swagger-ui-dist@5"),
+            path = config.path
+        ),
+        OpenApiUi::Redoc => format!(
+            r#"<!doctype html>
+<html>
+<head>
+<title>{title}</title>
+</head>
+<body>
+<redoc spec-url="{path}"></redoc>
+<script src="{assets}/redoc.standalone.js"></script>
+</body>
+</html>"#,
+            title = spec.info.title,
+            assets = assets("https://cdn.redoc.ly/redoc/latest/bundles"),
+            path = config.path
+        ),
+    };
+    router = router.route(&config.ui_path, get(move || async move { Html(page) }));
+    router
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{router, OpenApi};
+    use crate::config::{OpenApiConfig, OpenApiUi};
+    use crate::AppState;
+    use axum::body::Body;
+    use springboot::App;
+    use std::sync::Arc;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_ui_assets() {
+        let page = |config: OpenApiConfig| async move {
+            let state = AppState {
+                app: Arc::new(App::default()),
+            };
+            let router = router(&config, OpenApi::new("blog", "1.0.0", &[])).with_state(state);
+            let response = router.oneshot(Request::get("/docs").body(Body::empty()).unwrap()).await.unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        };
+        let swagger = OpenApiConfig {
+            ui: OpenApiUi::Swagger,
+            ..Default::default()
+        };
+        assert!(page(swagger.clone()).await.contains(r#"src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js""#));
+        let offline = OpenApiConfig {
+            ui_assets: Some("/static/swagger-ui".to_string()),
+            ..swagger
+        };
+        let page = page(offline).await;
+        assert!(page.contains(r#"href="/static/swagger-ui/swagger-ui.css""#));
+        assert!(page.contains(r#"src="/static/swagger-ui/swagger-ui-bundle.js""#));
+    }
+}
@@ -0,0 +1,240 @@
+//! [RFC 7807](https://tools.ietf.org/html/rfc7807) `application/problem+json` error bodies,
+//! configured under `[web.error]`:
+//!
+//! ```toml
+//! [web.error]
+//! format = "problem"
+//! expose_details = false
+//! type_base = "https://errors.example.com/"
+//! ```
+use crate::config::{ErrorConfig, ErrorFormat};
+use axum::extract::Request;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use springboot::component::ComponentRegistry;
+use springboot::config::env::Env;
+use springboot::App;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Header carrying the trace id of a request
+pub const TRACE_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST: RequestContext;
+}
+
+/// Trace id and path of the request being handled
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    /// The `x-request-id` header of the request, or a generated id
+    pub trace_id: String,
+    /// Path of the request
+    pub path: String,
+}
+
+impl RequestContext {
+    /// Context of the request handled by the current task
+    pub fn current() -> Option<Self> {
+        REQUEST.try_with(Clone::clone).ok()
+    }
+}
+
+fn generate_trace_id() -> String {
+    let random = RandomState::new().build_hasher().finish();
+    format!("{random:016x}")
+}
+
+/// Run the request in its [RequestContext] and echo the trace id in the response
+pub(crate) async fn request_context(req: Request, next: Next) -> Response {
+    let trace_id = req
+        .headers()
+        .get(TRACE_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+        .unwrap_or_else(generate_trace_id);
+    let context = RequestContext {
+        trace_id: trace_id.clone(),
+        path: req.uri().path().to_string(),
+    };
+    let mut response = REQUEST.scope(context, next.run(req)).await;
+    if let Ok(value) = HeaderValue::from_str(&trace_id) {
+        response.headers_mut().insert(TRACE_ID_HEADER, value);
+    }
+    response
+}
+
+/// `[web.error]` resolved against the active environment, registered as a component by the web starter
+#[derive(Debug, Clone)]
+pub struct ErrorSettings {
+    /// Format of the error bodies
+    pub format: ErrorFormat,
+    /// Whether internal error messages are sent to the client
+    pub expose_details: bool,
+    /// Prefix of the `type` uri, followed by the status code
+    pub type_base: Option<String>,
+}
+
+impl Default for ErrorSettings {
+    fn default() -> Self {
+        Self {
+            format: ErrorFormat::Problem,
+            expose_details: false,
+            type_base: None,
+        }
+    }
+}
+
+impl ErrorSettings {
+    /// Internal details are exposed outside of prod unless configured
+    pub(crate) fn new(config: &ErrorConfig, env: Env) -> Self {
+        Self {
+            format: config.format,
+            expose_details: config.expose_details.unwrap_or(!matches!(env, Env::Prod)),
+            type_base: config.type_base.clone(),
+        }
+    }
+
+    /// Settings of the current app, the defaults outside of an app
+    pub fn current() -> Self {
+        App::try_current()
+            .ok()
+            .and_then(|app| app.get_component::<Self>())
+            .unwrap_or_default()
+    }
+}
+
+/// An `application/problem+json` body
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    /// Uri identifying the problem type, `about:blank` by default
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Reason phrase of the status
+    pub title: String,
+    /// Status code
+    pub status: u16,
+    /// Explanation of this occurrence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Path of the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Trace id of the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// Extension members
+    #[serde(flatten)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+    /// Code of the response envelope, looked up by status if not set
+    #[serde(skip)]
+    pub code: Option<i64>,
+}
+
+impl Problem {
+    /// Problem of the given status for the current request
+    pub fn new(status: StatusCode, detail: Option<String>) -> Self {
+        let settings = ErrorSettings::current();
+        let context = RequestContext::current();
+        Self {
+            problem_type: match &settings.type_base {
+                Some(base) => format!("{base}{}", status.as_u16()),
+                None => "about:blank".to_string(),
+            },
+            title: status.canonical_reason().unwrap_or("Unknown").to_string(),
+            status: status.as_u16(),
+            detail,
+            instance: context.as_ref().map(|c| c.path.clone()),
+            trace_id: context.map(|c| c.trace_id),
+            extensions: Default::default(),
+            code: None,
+        }
+    }
+
+    /// Add an extension member
+    pub fn with_extensions(mut self, extensions: serde_json::Map<String, serde_json::Value>) -> Self {
+        self.extensions.extend(extensions);
+        self
+    }
+
+    fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let mut response = if let ErrorFormat::Plain = ErrorSettings::current().format {
+            let text = self.detail.clone().unwrap_or_else(|| self.title.clone());
+            (status, text).into_response()
+        } else {
+            let body = serde_json::to_vec(&self).expect("problem is always serializable");
+            (
+                status,
+                [(header::CONTENT_TYPE, HeaderValue::from_static("application/problem+json"))],
+                body,
+            )
+                .into_response()
+        };
+        // the response envelope is rendered from the problem
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{request_context, Problem, TRACE_ID_HEADER};
+    use crate::error::{KnownWebError, WebError};
+    use crate::Router;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_problem() {
+        let router = Router::new()
+            .route(
+                "/posts/{id}",
+                get(|| async {
+                    Err::<(), _>(WebError::from(
+                        KnownWebError::not_found("post not found").with_extension("id", 42),
+                    ))
+                }),
+            )
+            .route(
+                "/boom",
+                get(|| async { Err::<(), _>(WebError::from(anyhow::anyhow!("password=secret"))) }),
+            )
+            .layer(axum::middleware::from_fn(request_context));
+        let call = |uri: &'static str| {
+            let router = router.clone();
+            async move {
+                let req = Request::get(uri).header(TRACE_ID_HEADER, "abc").body(Body::empty()).unwrap();
+                let response = router.oneshot(req).await.unwrap();
+                assert_eq!(response.headers()["content-type"], "application/problem+json");
+                assert_eq!(response.headers()[TRACE_ID_HEADER], "abc");
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        let problem = call("/posts/42").await;
+        assert_eq!(problem["type"], "about:blank");
+        assert_eq!(problem["title"], "Not Found");
+        assert_eq!(problem["status"], 404);
+        assert_eq!(problem["detail"], "post not found");
+        assert_eq!(problem["instance"], "/posts/42");
+        assert_eq!(problem["trace_id"], "abc");
+        assert_eq!(problem["id"], 42);
+
+        // internal details are hidden by default
+        let problem = call("/boom").await;
+        assert_eq!(problem["status"], 500);
+        assert!(problem.get("detail").is_none());
+    }
+}
@@ -1,21 +1,32 @@
-use crate::banner;
-use crate::component::component::ComponentRef;
-use crate::component::{service, ComponentRegistry, MutableComponentRegistry, Starter};
+use crate::banner::{self, Banner};
+use crate::cache;
+use crate::component::component::{ComponentEntry, ComponentMut, ComponentRef};
+use crate::component::graph::{ComponentDefinition, ComponentGraph, ComponentOrigin, StarterDefinition};
+use crate::component::{self, service, ComponentRegistry, MutableComponentRegistry, Starter};
 use crate::config::env::Env;
 use crate::config::toml::TomlConfigRegistry;
 use crate::config::ConfigRegistry;
+use crate::config::spring::{BannerMode, SpringConfig};
+use crate::executor::{self, UncaughtErrorHandler};
+use crate::info::{self, AppInfo};
+use crate::interceptor;
+use crate::resilience;
+use crate::runner::{self, ApplicationArguments, ApplicationRunner, CommandLineRunner, RunnerRef};
+use crate::timeline::{StartupPhase, StartupTimeline};
 use crate::{
     component::{component::DynComponentRef, PluginRef},
-    error::Result,
+    error::{AppError, Result},
 };
 use dashmap::DashMap;
 use std::any::{Any, TypeId};
+use std::cell::RefCell;
 use std::str::FromStr;
-use std::sync::OnceLock;
+use std::sync::RwLock;
 use std::{collections::HashSet, future::Future, path::Path, sync::Arc};
 
 type Registry<T> = DashMap<TypeId, T>;
-type Scheduler<T> = dyn FnOnce(Arc<App>) -> Box<dyn Future<Output=Result<T>> + Send>;
+pub(crate) type Scheduler<T> = dyn FnOnce(Arc<App>) -> Box<dyn Future<Output=Result<T>> + Send>;
+type StartupCheck = dyn FnOnce(&AppBuilder) -> Result<()>;
 /// Running Applications
 #[derive(Default)]
 pub struct App {
@@ -23,6 +34,8 @@ pub struct App {
     /// Component
     components: Registry<DynComponentRef>,
     config: TomlConfigRegistry,
+    /// Starters and components registered while building
+    graph: ComponentGraph,
 }
 
 /// AppBuilder: Application under construction
@@ -37,13 +50,39 @@ pub struct AppBuilder {
     pub logger_handler: Option<PluginRef>,
     /// Plugin
     pub(crate) plugin_registry: Registry<PluginRef>,
+    /// Starters built as soon as they were added, see [`Starter::immediately`]
+    immediate_starters: HashSet<TypeId>,
     /// Component
     components: Registry<DynComponentRef>,
+    /// Components replaced by [`AppBuilder::override_component`]
+    overridden: HashSet<TypeId>,
+    /// Starters and components registered so far
+    graph: ComponentGraph,
+    /// The starter being built, it is the origin of the components added meanwhile
+    building_starter: Option<String>,
     /// Configuration read from `config_path`
     config: TomlConfigRegistry,
+    /// Whether the configuration was provided by `use_config_file` or `use_config_str`
+    config_provided: bool,
     /// task
     schedulers: Vec<Box<Scheduler<String>>>,
-    shutdown_hooks: Vec<Box<Scheduler<String>>>,
+    pub(crate) shutdown_hooks: Vec<Box<Scheduler<String>>>,
+    /// Checks run once the services are installed
+    startup_checks: Vec<Box<StartupCheck>>,
+    /// Runners executed once the application is ready
+    runners: Vec<RunnerRef>,
+    /// Command line arguments
+    args: ApplicationArguments,
+    /// Whether the built app is published as the process-wide global app
+    use_global: bool,
+    /// Whether the `#[starter]` structs linked into the binary are installed
+    starter_discovery: bool,
+    /// Timing of the startup phases
+    pub(crate) timeline: StartupTimeline,
+    /// Custom banner replacing the built-in one
+    pub(crate) banner: Option<Box<dyn Banner>>,
+    /// Handler for the errors of unobserved `#[async_method]` calls
+    pub(crate) uncaught_error_handler: Option<Arc<dyn UncaughtErrorHandler>>,
 }
 
 impl App {
@@ -59,27 +98,85 @@ impl App {
         self.env
     }
 
-    /// Returns an instance of the currently configured global [`App`].
+    /// The starters and components of this app, see [ComponentGraph]
+    pub fn component_graph(&self) -> &ComponentGraph {
+        &self.graph
+    }
+
+    /// Returns the [`App`] the current code runs in.
+    ///
+    /// The app is looked up in this order:
+    /// 1. the app of the current task, see [`App::scope`]
+    /// 2. the app scoped to the current thread, see [`TestApp`](crate::testing::TestApp)
+    /// 3. the process-wide global app, i.e. the most recently built app,
+    ///    unless disabled by [`AppBuilder::use_global`]
+    ///
+    /// Schedulers, runners, commands and web requests are executed in the scope of their app,
+    /// so several apps can run in one process.
+    pub fn current() -> Arc<App> {
+        Self::try_current().expect("no app is running in the current context")
+    }
+
+    /// Like [`App::current`], but returns [`AppError::AppNotBuilt`] instead of panicking.
+    pub fn try_current() -> Result<Arc<App>> {
+        CURRENT_APP
+            .try_with(Clone::clone)
+            .ok()
+            .or_else(|| SCOPED_APP.with_borrow(Clone::clone))
+            .or_else(|| GLOBAL_APP.read().unwrap().clone())
+            .ok_or(AppError::AppNotBuilt)
+    }
+
+    /// Run the future with the given app as the [current app](App::current) of the task.
+    pub async fn scope<F: Future>(app: Arc<App>, f: F) -> F::Output {
+        CURRENT_APP.scope(app, f).await
+    }
+
+    /// Returns an instance of the [current app](App::current).
     ///
     /// **NOTE**: This global App is initialized after the application is built,
     /// please use it when the app is running, don't use it during the build process,
     /// such as during the plug-in build process.
     pub fn global() -> Arc<App> {
-        GLOBAL_APP.get().unwrap().clone()
+        Self::current()
+    }
+
+    /// Like [`App::global`], but returns `None` before the application is built.
+    pub fn try_global() -> Option<Arc<App>> {
+        Self::try_current().ok()
     }
 
     fn set_global(app: Arc<App>) {
-        GLOBAL_APP.get_or_init(|| app);
+        *GLOBAL_APP.write().unwrap() = Some(app);
+    }
+
+    /// Scope the global app to the current thread, returns the previously scoped app
+    pub(crate) fn set_scoped(app: Option<Arc<App>>) -> Option<Arc<App>> {
+        SCOPED_APP.replace(app)
     }
 }
 
-/// Application Builder
+/// Get the service component from the [current app](App::current).
+/// It panics if there is no current app or the component doesn't exist.
 pub fn get_service<T: Send + Sync + Clone + 'static>() -> T {
-    GLOBAL_APP.get().unwrap().get_component::<T>().unwrap()
+    try_get_service::<T>().unwrap()
 }
 
+/// Get the service component from the [current app](App::current).
+pub fn try_get_service<T: Send + Sync + Clone + 'static>() -> Result<T> {
+    App::try_current()?.try_get_component::<T>()
+}
+
+
+static GLOBAL_APP: RwLock<Option<Arc<App>>> = RwLock::new(None);
+
+thread_local! {
+    static SCOPED_APP: RefCell<Option<Arc<App>>> = const { RefCell::new(None) };
+}
 
-static GLOBAL_APP: OnceLock<Arc<App>> = OnceLock::new();
+tokio::task_local! {
+    static CURRENT_APP: Arc<App>;
+}
 
 unsafe impl Send for AppBuilder {}
 unsafe impl Sync for AppBuilder {}
@@ -96,7 +193,10 @@ impl AppBuilder {
     pub fn add_starter<T: Starter>(&mut self, plugin: T) -> &mut Self {
         log::debug!("added component: {}", plugin.name());
         if plugin.immediately() {
-            plugin.immediately_build(self);
+            self.immediate_starters.insert(TypeId::of::<T>());
+            self.build_starter(&PluginRef::new(plugin), |plugin, app| {
+                plugin.immediately_build(app)
+            });
             return self;
         }
         let plugin_id = TypeId::of::<T>();
@@ -118,7 +218,8 @@ impl AppBuilder {
     /// Returns `true` if the [`Starter`] has already been added.
     #[inline]
     pub fn is_plugin_added<T: Starter>(&self) -> bool {
-        self.plugin_registry.contains_key(&TypeId::of::<T>())
+        let plugin_id = TypeId::of::<T>();
+        self.plugin_registry.contains_key(&plugin_id) || self.immediate_starters.contains(&plugin_id)
     }
 
     /// The path of the configuration file, default is `./config/app.toml`.
@@ -130,8 +231,14 @@ impl AppBuilder {
     ///
     /// For specific supported environments, see the [Env](../config/env/enum.Env.html) enum.
     pub fn use_config_file(&mut self, config_path: &str) -> &mut Self {
-        self.config = TomlConfigRegistry::new(Path::new(config_path), self.env)
+        let env = self.env;
+        self.config = self
+            .timeline
+            .time(StartupPhase::Config, config_path, || {
+                TomlConfigRegistry::new(Path::new(config_path), env)
+            })
             .expect("config file load failed");
+        self.config_provided = true;
         self
     }
 
@@ -140,12 +247,15 @@ impl AppBuilder {
     ///
     /// **Note**: This configuration method only supports one configuration content and does not support multiple environments.
     pub fn use_config_str(&mut self, toml_content: &str) -> &mut Self {
-        self.config =
-            TomlConfigRegistry::from_str(toml_content).expect("config content parse failed");
+        self.config = self
+            .timeline
+            .time(StartupPhase::Config, "config string", || TomlConfigRegistry::from_str(toml_content))
+            .expect("config content parse failed");
+        self.config_provided = true;
         self
     }
 
-    /// add [tracing_subscriber::layer]
+    // add [tracing_subscriber::layer]
     // pub fn add_layer<L>(&mut self, layer: L) -> &mut Self
     // where
     //     L: Layer<tracing_subscriber::Registry> + Send + Sync + 'static,
@@ -172,64 +282,358 @@ impl AppBuilder {
         self
     }
 
+    /// Add a check run once the services are installed, an error fails [`run`](Self::run) and [`build`](Self::build).
+    ///
+    /// Starters use it to verify that the components they require were registered by the other starters.
+    pub fn add_startup_check<T>(&mut self, check: T) -> &mut Self
+    where
+        T: FnOnce(&AppBuilder) -> Result<()> + 'static,
+    {
+        self.startup_checks.push(Box::new(check));
+        self
+    }
+
+    /// Add a [CommandLineRunner] executed once the application is ready
+    pub fn add_command_line_runner<T: CommandLineRunner>(&mut self, runner: T) -> &mut Self {
+        self.runners.push(RunnerRef::CommandLine(Arc::new(runner)));
+        self
+    }
+
+    /// Add an [ApplicationRunner] executed once the application is ready
+    pub fn add_application_runner<T: ApplicationRunner>(&mut self, runner: T) -> &mut Self {
+        self.runners.push(RunnerRef::Application(Arc::new(runner)));
+        self
+    }
+
+    /// Replace the component of the same type with the given one, usually a mock in tests.
+    ///
+    /// The replacement wins over every later [`add_component`](MutableComponentRegistry::add_component)
+    /// of that type, and a [`Service`](crate::component::service::Service) of that type is not built at all.
+    pub fn override_component<C>(&mut self, component: C) -> &mut Self
+    where
+        C: Clone + Any + Send + Sync,
+    {
+        let component_id = TypeId::of::<C>();
+        log::debug!("override component: {}", std::any::type_name::<C>());
+        self.components
+            .insert(component_id, DynComponentRef::new(component));
+        self.overridden.insert(component_id);
+        self.graph
+            .components
+            .retain(|c| c.type_name != std::any::type_name::<C>());
+        self.define_component::<C>(ComponentOrigin::Override);
+        self
+    }
+
+    /// Mutably borrow a component added before, so starters can grow shared collections such as routers.
+    ///
+    /// Components already handed out as [ComponentRef] keep their old value.
+    pub fn get_component_mut<T>(&mut self) -> Option<ComponentMut<'_, T>>
+    where
+        T: Clone + Any + Send + Sync,
+    {
+        let component = self.components.get_mut(&TypeId::of::<T>())?;
+        ComponentMut::new(component)
+    }
+
+    /// Get the registry slot of the component for in-place manipulation.
+    ///
+    /// ```
+    /// use springboot::App;
+    ///
+    /// #[derive(Clone, Default)]
+    /// struct Jobs(Vec<&'static str>);
+    ///
+    /// let mut app = App::new();
+    /// app.component_entry::<Jobs>().or_default().0.push("cleanup");
+    /// app.component_entry::<Jobs>().or_default().0.push("report");
+    /// assert_eq!(app.get_component_mut::<Jobs>().unwrap().0, ["cleanup", "report"]);
+    /// ```
+    ///
+    /// Like [`add_component`](MutableComponentRegistry::add_component), it leaves a component
+    /// replaced by [`override_component`](AppBuilder::override_component) unchanged.
+    pub fn component_entry<T>(&mut self) -> ComponentEntry<'_, T>
+    where
+        T: Clone + Any + Send + Sync,
+    {
+        let component_id = TypeId::of::<T>();
+        if self.overridden.contains(&component_id) {
+            log::debug!("component {} is overridden, skipped", std::any::type_name::<T>());
+            return ComponentEntry::new(None);
+        }
+        if !self.components.contains_key(&component_id) {
+            self.define_component::<T>(self.current_origin());
+        }
+        ComponentEntry::new(Some(self.components.entry(component_id)))
+    }
+
+    /// Describe the dependencies and the configuration of a component, it shows up in the [ComponentGraph].
+    /// The `Service` derive calls it before building the service,
+    /// so a service that failed to build is still listed with its missing dependencies.
+    pub fn describe_component<C: Any>(
+        &mut self,
+        origin: ComponentOrigin,
+        config_prefixes: Vec<&'static str>,
+        dependencies: Vec<&'static str>,
+    ) -> &mut Self {
+        let definition = self.define_component::<C>(origin.clone());
+        definition.origin = origin;
+        definition.config_prefixes = config_prefixes;
+        definition.dependencies = dependencies;
+        self
+    }
+
+    /// The starters and components registered so far, see [ComponentGraph]
+    pub fn component_graph(&self) -> &ComponentGraph {
+        &self.graph
+    }
+
+    fn define_component<C: Any>(&mut self, origin: ComponentOrigin) -> &mut ComponentDefinition {
+        let type_name = std::any::type_name::<C>();
+        let components = &mut self.graph.components;
+        let index = match components.iter().position(|c| c.type_name == type_name) {
+            Some(index) => index,
+            None => {
+                components.push(ComponentDefinition::new(type_name, origin));
+                components.len() - 1
+            }
+        };
+        &mut components[index]
+    }
+
+    fn current_origin(&self) -> ComponentOrigin {
+        match &self.building_starter {
+            Some(name) => ComponentOrigin::Starter(name.clone()),
+            None => ComponentOrigin::Manual,
+        }
+    }
+
+    fn build_starter(&mut self, plugin: &PluginRef, build: impl FnOnce(&PluginRef, &mut Self)) {
+        self.graph.starters.push(StarterDefinition {
+            name: plugin.name().to_string(),
+            dependencies: plugin.dependencies().iter().map(|d| d.to_string()).collect(),
+        });
+        let previous = self.building_starter.replace(plugin.name().to_string());
+        let timeline = self.timeline.clone();
+        timeline.time(StartupPhase::Starter, plugin.name(), || build(plugin, self));
+        self.building_starter = previous;
+    }
+
+    /// Returns `true` if the component has been replaced by [`AppBuilder::override_component`].
+    #[inline]
+    pub fn is_component_overridden<C: Any>(&self) -> bool {
+        self.overridden.contains(&TypeId::of::<C>())
+    }
+
+    /// Whether the built app is published as the process-wide global app, default is `true`.
+    ///
+    /// Disable it when several apps run in one process, the app is then only reachable
+    /// through [`App::current`] inside its own schedulers, runners, commands and requests.
+    pub fn use_global(&mut self, enable: bool) -> &mut Self {
+        self.use_global = enable;
+        self
+    }
+
+    /// Whether the starters annotated with `#[starter]` are discovered and installed, default is `true`.
+    ///
+    /// When disabled, only the starters added by [`AppBuilder::add_starter`] and [`AppBuilder::logger`] are built.
+    /// ```
+    /// use springboot::component::{ComponentRegistry, MutableComponentRegistry, Starter};
+    /// use springboot::application::AppBuilder;
+    /// use springboot::App;
+    ///
+    /// #[derive(Clone)]
+    /// struct Discovered;
+    ///
+    /// #[springboot::component::starter]
+    /// struct DiscoveredStarter;
+    ///
+    /// impl Starter for DiscoveredStarter {
+    ///     fn build(&self, app: &mut AppBuilder) {
+    ///         app.add_component(Discovered);
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let app = App::new().use_config_str("").use_global(false).build().await.unwrap();
+    /// assert!(app.has_component::<Discovered>());
+    ///
+    /// let app = App::new()
+    ///     .use_config_str("[spring.autoconfigure]\nexclude = [\"DiscoveredStarter\"]")
+    ///     .use_global(false)
+    ///     .build()
+    ///     .await
+    ///     .unwrap();
+    /// assert!(!app.has_component::<Discovered>());
+    ///
+    /// let app = App::new().use_config_str("").use_global(false).use_starter_discovery(false).build().await.unwrap();
+    /// assert!(!app.has_component::<Discovered>());
+    /// # }
+    /// ```
+    pub fn use_starter_discovery(&mut self, enable: bool) -> &mut Self {
+        self.starter_discovery = enable;
+        self
+    }
+
+    /// Register the [AppInfo] of the application as a component, usually created by [app_info](crate::app_info).
+    /// The banner shows its name, version and git commit.
+    /// Without it the app registers an [AppInfo] of `spring.application`, without build details.
+    pub fn app_info(&mut self, info: AppInfo) -> &mut Self {
+        self.add_component(info)
+    }
+
+    /// Handle the errors of `#[async_method]` calls whose result is not awaited, they are logged by default
+    pub fn uncaught_error_handler<H: UncaughtErrorHandler>(&mut self, handler: H) -> &mut Self {
+        self.uncaught_error_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Print the given banner at startup instead of the built-in one or `banner.txt`.
+    /// Whether and where it is printed is still controlled by `spring.banner.mode`.
+    pub fn banner<B: Banner>(&mut self, banner: B) -> &mut Self {
+        self.banner = Some(Box::new(banner));
+        self
+    }
+
+    /// Use the given command line arguments instead of the arguments of the current process
+    pub fn use_args(&mut self, args: ApplicationArguments) -> &mut Self {
+        self.args = args;
+        self
+    }
+
     /// The `run` method is suitable for applications that contain scheduling logic,
     /// such as web, job, and stream.
     ///
     /// * [spring-web](https://docs.rs/spring-web)
     /// * [spring-job](https://docs.rs/spring-job)
     /// * [spring-stream](https://docs.rs/spring-stream)
+    ///
+    /// If the first non-option argument or the `--command=<name>` option names a `#[command]`,
+    /// that command is executed instead of the schedulers and the process exits with the command's status.
+    /// An unknown command name exits with status 2.
     pub async fn run(&mut self) {
         match self.inner_run().await {
             Err(e) => {
                 log::error!("{:?}", e);
             }
+            Ok(Some(exit_code)) => std::process::exit(exit_code),
             _ => { /* ignore */ }
         }
     }
 
-    async fn inner_run(&mut self) -> Result<()> {
+    async fn inner_run(&mut self) -> Result<Option<i32>> {
         // 1. load toml config
         self.load_config_if_need()?;
 
-        banner::print_banner(self);
+        banner::print_banner(self, BannerMode::Console);
 
         // 2. build plugin
+        if self.starter_discovery {
+            component::auto_discover_starters(self)?;
+        }
+        self.add_args_component();
+        self.add_timeline_component();
+        info::add_app_info_component(self);
         self.build_plugins().await;
+        executor::add_executor_component(self)?;
+        cache::add_cache_component(self)?;
+        resilience::add_resilience_component(self)?;
+        banner::print_banner(self, BannerMode::Log);
 
         // 3. service dependency inject
         service::auto_inject_service(self)?;
+        self.run_startup_checks()?;
 
-        // 4. schedule
-        self.schedule().await
+        // 4. one-off command
+        match runner::find_command(&self.args) {
+            Ok(Some(command)) => return self.run_command(command).await.map(Some),
+            Ok(None) => {}
+            Err(e) => {
+                // a usage error, the process exits like for an invalid argument
+                log::error!("{e}");
+                return Ok(Some(2));
+            }
+        }
+
+        // 5. schedule
+        self.schedule().await.map(|_| None)
     }
 
     /// Unlike the [`run`] method, the `build` method is suitable for applications that do not contain scheduling logic.
     /// This method returns the built App, and developers can implement logic such as command lines and task scheduling by themselves.
+    /// Runners and `#[command]` functions are only executed by [`run`].
     pub async fn build(&mut self) -> Result<Arc<App>> {
         // 1. load toml config
         self.load_config_if_need()?;
 
         // 2. build plugin
+        if self.starter_discovery {
+            component::auto_discover_starters(self)?;
+        }
+        self.add_args_component();
+        self.add_timeline_component();
+        info::add_app_info_component(self);
         self.build_plugins().await;
+        executor::add_executor_component(self)?;
+        cache::add_cache_component(self)?;
+        resilience::add_resilience_component(self)?;
 
         // 3. service dependency inject
         service::auto_inject_service(self)?;
+        self.run_startup_checks()?;
 
-        Ok(self.build_app())
+        let app = self.build_app();
+        self.report_timeline(&app);
+        Ok(app)
+    }
+
+    fn run_startup_checks(&mut self) -> Result<()> {
+        for check in std::mem::take(&mut self.startup_checks) {
+            check(self)?;
+        }
+        interceptor::check_interceptors(self)
     }
 
     fn load_config_if_need(&mut self) -> Result<()> {
-        if self.config.is_empty() {
-            self.config = TomlConfigRegistry::new(Path::new("./config/app.toml"), self.env)?;
+        if !self.config_provided && self.config.is_empty() {
+            let config_path = Path::new("./config/app.toml");
+            let env = self.env;
+            self.config = self.timeline.time(StartupPhase::Config, "./config/app.toml", || {
+                TomlConfigRegistry::new(config_path, env)
+            })?;
         }
         Ok(())
     }
 
+    fn add_args_component(&mut self) {
+        if !self.has_component::<ApplicationArguments>() {
+            self.add_component(self.args.clone());
+        }
+    }
+
+    fn add_timeline_component(&mut self) {
+        if !self.has_component::<StartupTimeline>() {
+            self.add_component(self.timeline.clone());
+        }
+    }
+
+    /// Print the startup timeline if `spring.startup.timeline` is enabled
+    fn report_timeline(&self, app: &App) {
+        let enabled = app
+            .get_config::<SpringConfig>()
+            .map(|config| config.startup.timeline)
+            .unwrap_or_default();
+        if enabled {
+            log::info!("{}", self.timeline.report());
+        }
+    }
+
     async fn build_plugins(&mut self) {
 
         // 1. build logger
         if let Some(logger) = self.logger_handler.clone() {
-            logger.build(self);
+            self.build_starter(&logger, |logger, app| logger.build(app));
         }
 
         // LogPlugin.immediately_build(self);
@@ -247,7 +651,7 @@ impl AppBuilder {
             for plugin in to_register {
                 let deps = plugin.dependencies();
                 if deps.iter().all(|dep| registered.contains(*dep)) {
-                    plugin.build(self);
+                    self.build_starter(&plugin, |plugin, app| plugin.build(app));
                     registered.insert(plugin.name().to_string());
                     log::info!("{} component registered", plugin.name());
                     progress = true;
@@ -269,11 +673,24 @@ impl AppBuilder {
         let app = self.build_app();
 
         let schedulers = std::mem::take(&mut self.schedulers);
-        let mut handles = vec![];
-        for task in schedulers {
-            let poll_future = task(app.clone());
-            let poll_future = Box::into_pin(poll_future);
-            handles.push(tokio::spawn(poll_future));
+        let mut handles = self.timeline.time(StartupPhase::Scheduler, "spawn schedulers", || {
+            schedulers
+                .into_iter()
+                .map(|task| {
+                    let poll_future = Box::into_pin(task(app.clone()));
+                    tokio::spawn(App::scope(app.clone(), poll_future))
+                })
+                .collect::<Vec<_>>()
+        });
+        self.report_timeline(&app);
+
+        // runners are executed after the schedulers have been started
+        let mut runners = std::mem::take(&mut self.runners);
+        runners.extend(runner::discover_runners(&app));
+        let runners = runner::call_runners(runners, app.clone(), &self.args);
+        if let Err(e) = App::scope(app.clone(), runners).await {
+            handles.iter().for_each(|handle| handle.abort());
+            return Err(e);
         }
 
         while let Some(handle) = handles.pop() {
@@ -283,15 +700,29 @@ impl AppBuilder {
             }
         }
 
+        self.shutdown(app).await
+    }
+
+    async fn run_command(&mut self, command: &dyn runner::CommandRegistrar) -> Result<i32> {
+        let app = self.build_app();
+        log::info!("run command: {}", command.name());
+        let run = command.run(app.clone(), self.args.clone());
+        let exit_code = App::scope(app.clone(), run).await?;
+        log::info!("command {} finished with exit code {exit_code}", command.name());
+        self.shutdown(app).await?;
+        Ok(exit_code)
+    }
+
+    async fn shutdown(&mut self, app: Arc<App>) -> Result<()> {
         // FILO: The hooks added by the plugin built first should be executed later
         while let Some(hook) = self.shutdown_hooks.pop() {
-            let result = Box::into_pin(hook(app.clone())).await?;
+            let result = App::scope(app.clone(), Box::into_pin(hook(app.clone()))).await?;
             log::info!("shutdown result: {result}");
         }
         Ok(())
     }
 
-    fn build_app(&mut self) -> Arc<App> {
+    pub(crate) fn build_app(&mut self) -> Arc<App> {
         let components = std::mem::take(&mut self.components);
         // let G:std::sync::OnceLock<&Registry<DynComponentRef>> = std::sync::OnceLock::new();
         // G.get_or_init(||&components);
@@ -300,9 +731,12 @@ impl AppBuilder {
             env: self.env,
             components,
             config,
+            graph: std::mem::take(&mut self.graph),
         });
 
-        App::set_global(app.clone());
+        if self.use_global {
+            App::set_global(app.clone());
+        }
         app
     }
 }
@@ -314,10 +748,23 @@ impl Default for AppBuilder {
             // layers: Default::default(),
             logger_handler: None,
             plugin_registry: Default::default(),
+            immediate_starters: Default::default(),
             config: Default::default(),
+            config_provided: false,
             components: Default::default(),
+            overridden: Default::default(),
+            graph: Default::default(),
+            building_starter: None,
             schedulers: Default::default(),
             shutdown_hooks: Default::default(),
+            startup_checks: Default::default(),
+            runners: Default::default(),
+            args: ApplicationArguments::from_env(),
+            use_global: true,
+            starter_discovery: true,
+            timeline: StartupTimeline::new(),
+            banner: None,
+            uncaught_error_handler: None,
         }
     }
 }
@@ -383,18 +830,24 @@ impl MutableComponentRegistry for AppBuilder {
     {
         let component_id = TypeId::of::<C>();
         let component_name = std::any::type_name::<C>();
+        if self.overridden.contains(&component_id) {
+            log::debug!("component {} is overridden, skipped", component_name);
+            return self;
+        }
         log::debug!("added component: {}", component_name);
         if self.components.contains_key(&component_id) {
             panic!("Error adding component {component_name}: component was already added in application")
         }
         self.components
             .insert(component_id, DynComponentRef::new(component));
+        self.define_component::<C>(self.current_origin());
         self
     }
 }
 
 #[allow(unused_imports)]
 mod tests {
+    use super::{get_service, try_get_service};
     use crate::component::{ComponentRegistry, MutableComponentRegistry};
     use crate::App;
 
@@ -441,4 +894,58 @@ mod tests {
         let p = app.get_component::<Point<i32>>();
         assert!(p.is_none())
     }
+
+    #[tokio::test]
+    async fn test_multiple_apps() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Tenant(&'static str);
+
+        let first = App::new()
+            .use_global(false)
+            .add_component(Tenant("first"))
+            .build()
+            .await
+            .expect("app build failed");
+        let second = App::new()
+            .use_global(false)
+            .add_component(Tenant("second"))
+            .build()
+            .await
+            .expect("app build failed");
+
+        let tenant = App::scope(first, async { get_service::<Tenant>() }).await;
+        assert_eq!(tenant, Tenant("first"));
+        let tenant = App::scope(second, async { try_get_service::<Tenant>() }).await;
+        assert_eq!(tenant.unwrap(), Tenant("second"));
+        assert!(try_get_service::<Tenant>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_component_entry() {
+        #[derive(Clone, Default)]
+        struct Jobs(Vec<&'static str>);
+
+        let mut builder = App::new();
+        builder.component_entry::<Jobs>().or_default().0.push("cleanup");
+        let before = builder.get_expect_component_ref::<Jobs>();
+        builder.get_component_mut::<Jobs>().unwrap().0.push("report");
+
+        assert_eq!(before.0, ["cleanup"]);
+        assert!(builder.get_component_mut::<i32>().is_none());
+        let app = builder.build().await.expect("app build failed");
+        assert_eq!(app.get_expect_component::<Jobs>().0, ["cleanup", "report"]);
+    }
+
+    #[tokio::test]
+    async fn test_component_entry_overridden() {
+        #[derive(Clone, Default)]
+        struct Jobs(Vec<&'static str>);
+
+        let mut builder = App::new();
+        builder.override_component(Jobs(vec!["mock"]));
+        builder.component_entry::<Jobs>().or_default().0.push("cleanup");
+
+        let app = builder.build().await.expect("app build failed");
+        assert_eq!(app.get_expect_component::<Jobs>().0, ["mock"]);
+    }
 }
@@ -1,6 +1,10 @@
+use crate::component::ComponentRegistry;
+use crate::config::spring::{BannerMode, SpringConfig};
+use crate::info::AppInfo;
+use crate::{application::AppBuilder, config::env::Env, config::ConfigRegistry};
 use nu_ansi_term::Color;
-
-use crate::{application::AppBuilder, config::env::Env};
+use std::io::IsTerminal;
+use std::path::Path;
 
 const BANNER: &str = r"
             ⌡
@@ -14,21 +18,139 @@ _▄▒▓▒▒▓▒▒░▓▓▓▓▓  ▓██▄_   ███~ ~██
  ▓▀▀▀ⁿª^                 ▀▀▀                                ▀▀▀▀▀▀▀~
 ";
 
-pub(crate) fn print_banner(app: &AppBuilder) {
-    println!("{BANNER}");
-    println!(
-        "     spring: {}",
-        Color::Green.paint(env!("CARGO_PKG_VERSION"))
-    );
-    let env = match app.env {
-        Env::Dev => Color::LightYellow.paint("Dev"),
-        Env::Test => Color::LightBlue.paint("Test"),
-        Env::Prod => Color::Green.paint("Prod"),
+/// Values available to a [Banner]
+#[derive(Debug, Clone)]
+pub struct BannerContext {
+    /// `spring.application.name`, or the name of the [AppInfo] component, or the file name of the executable
+    pub app_name: String,
+    /// `spring.application.version`, or the version of the [AppInfo] component
+    pub app_version: String,
+    /// The active environment
+    pub profile: Env,
+    /// The git commit of the [AppInfo] component, or the `GIT_COMMIT` environment variable
+    pub git_commit: String,
+    /// Version of springboot
+    pub spring_version: &'static str,
+    /// Whether the banner goes to the console and stdout is a terminal, so it may use ansi colors
+    pub colored: bool,
+}
+
+/// Renders the text printed at startup, see [`AppBuilder::banner`]
+pub trait Banner: Send + Sync + 'static {
+    /// Render the banner
+    fn render(&self, context: &BannerContext) -> String;
+}
+
+/// The built-in spring banner
+pub struct SpringBanner;
+
+impl Banner for SpringBanner {
+    fn render(&self, context: &BannerContext) -> String {
+        let paint = |color: Color, text: &str| match context.colored {
+            true => color.paint(text).to_string(),
+            false => text.to_string(),
+        };
+        let env = match context.profile {
+            Env::Dev => paint(Color::LightYellow, "Dev"),
+            Env::Test => paint(Color::LightBlue, "Test"),
+            Env::Prod => paint(Color::Green, "Prod"),
+        };
+        let compilation = if cfg!(debug_assertions) {
+            paint(Color::LightRed, "Debug")
+        } else {
+            paint(Color::Green, "Release")
+        };
+        format!(
+            "{BANNER}\n     spring: {}\nenvironment: {env}\ncompilation: {compilation}",
+            paint(Color::Green, context.spring_version)
+        )
+    }
+}
+
+/// A banner read from a text file, such as `./config/banner.txt`.
+///
+/// Placeholders: `${app.name}`, `${app.version}`, `${spring.profile}`, `${git.commit}` and `${spring.version}`.
+pub struct TextBanner(pub String);
+
+impl Banner for TextBanner {
+    fn render(&self, context: &BannerContext) -> String {
+        self.0
+            .replace("${app.name}", &context.app_name)
+            .replace("${app.version}", &context.app_version)
+            .replace("${spring.profile}", &format!("{:?}", context.profile))
+            .replace("${git.commit}", &context.git_commit)
+            .replace("${spring.version}", context.spring_version)
+    }
+}
+
+/// Print the banner if `spring.banner.mode` equals the given mode.
+/// The console banner is printed before the starters are built,
+/// the log banner after them, once the logger is ready.
+pub(crate) fn print_banner(app: &AppBuilder, mode: BannerMode) {
+    let config = app.get_config::<SpringConfig>().unwrap_or_default();
+    if config.banner.mode != mode {
+        return;
+    }
+    let info = app.get_component::<AppInfo>();
+    let app_name = config
+        .application
+        .name
+        .or_else(|| info.as_ref().map(|info| info.name.to_string()))
+        .or_else(|| {
+            let exe = std::env::current_exe().ok()?;
+            Some(exe.file_stem()?.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+    let app_version = config
+        .application
+        .version
+        .or_else(|| info.as_ref().map(|info| info.version.to_string()))
+        .unwrap_or_default();
+    let git_commit = info
+        .and_then(|info| info.git_commit.map(str::to_string))
+        .or_else(|| std::env::var("GIT_COMMIT").ok())
+        .unwrap_or_else(|| "unknown".to_string());
+    let context = BannerContext {
+        app_name,
+        app_version,
+        profile: app.env,
+        git_commit,
+        spring_version: env!("CARGO_PKG_VERSION"),
+        colored: mode == BannerMode::Console && std::io::stdout().is_terminal(),
+    };
+    let location = Path::new(&config.banner.location);
+    let text = match (&app.banner, std::fs::read_to_string(location)) {
+        (Some(banner), _) => banner.render(&context),
+        (None, Ok(template)) => TextBanner(template).render(&context),
+        (None, Err(_)) => SpringBanner.render(&context),
     };
-    println!("environment: {}", env);
-    if cfg!(debug_assertions) {
-        println!("compilation: {}", Color::LightRed.paint("Debug"));
-    } else {
-        println!("compilation: {}", Color::Green.paint("Release"));
+    match mode {
+        BannerMode::Console => println!("{text}"),
+        BannerMode::Log => log::info!("\n{text}"),
+        BannerMode::Off => {}
+    }
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{Banner, BannerContext, SpringBanner, TextBanner};
+    use crate::config::env::Env;
+
+    #[test]
+    fn test_text_banner_placeholders() {
+        let context = BannerContext {
+            app_name: "blog".to_string(),
+            app_version: "1.2.0".to_string(),
+            profile: Env::Prod,
+            git_commit: "3f2a9c1".to_string(),
+            spring_version: "0.1.0",
+            colored: false,
+        };
+        let banner = TextBanner("${app.name} v${app.version} (${spring.profile}, ${git.commit}) ${unknown}".into());
+        assert_eq!(banner.render(&context), "blog v1.2.0 (Prod, 3f2a9c1) ${unknown}");
+
+        let spring = SpringBanner.render(&context);
+        assert!(spring.contains("environment: Prod"));
+        assert!(!spring.contains('\u{1b}'));
     }
 }
@@ -0,0 +1,65 @@
+use crate::config::Configurable;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// `[cache]`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CacheConfig {
+    /// Seconds an entry lives, entries don't expire if absent
+    pub ttl: Option<u64>,
+    /// Entries kept per cache by the in-memory backend, the least recently used are evicted first
+    #[serde(default = "default_max_capacity")]
+    pub max_capacity: usize,
+    /// Settings of single caches by name, overriding the ones above
+    #[serde(default)]
+    pub caches: HashMap<String, CacheSpec>,
+}
+
+impl Configurable for CacheConfig {
+    fn config_prefix() -> &'static str {
+        "cache"
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: None,
+            max_capacity: default_max_capacity(),
+            caches: Default::default(),
+        }
+    }
+}
+
+fn default_max_capacity() -> usize {
+    10_000
+}
+
+/// `[cache.caches.<name>]`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CacheSpec {
+    /// Seconds an entry lives
+    pub ttl: Option<u64>,
+    /// Entries kept by the in-memory backend
+    pub max_capacity: Option<usize>,
+}
+
+impl CacheConfig {
+    /// Time to live of the entries of the given cache
+    pub fn ttl(&self, cache: &str) -> Option<Duration> {
+        self.caches
+            .get(cache)
+            .and_then(|spec| spec.ttl)
+            .or(self.ttl)
+            .map(Duration::from_secs)
+    }
+
+    /// Capacity of the given cache
+    pub fn max_capacity(&self, cache: &str) -> usize {
+        self.caches
+            .get(cache)
+            .and_then(|spec| spec.max_capacity)
+            .unwrap_or(self.max_capacity)
+    }
+}
@@ -0,0 +1,115 @@
+use super::config::CacheConfig;
+use super::CacheBackend;
+use crate::error::Result;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// In-memory backend with time to live and least recently used eviction, the default backend
+pub struct MemoryCacheBackend {
+    config: CacheConfig,
+    caches: Mutex<HashMap<String, LruCache>>,
+}
+
+#[derive(Default)]
+struct LruCache {
+    entries: HashMap<String, Entry>,
+    /// access tick to key, the first one is the least recently used
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+struct Entry {
+    value: Vec<u8>,
+    expires_at: Option<Instant>,
+    tick: u64,
+}
+
+impl LruCache {
+    fn touch(&mut self, key: &str) -> u64 {
+        self.tick += 1;
+        self.order.insert(self.tick, key.to_string());
+        self.tick
+    }
+
+    fn get(&mut self, key: &str) -> Option<Vec<u8>> {
+        let entry = self.entries.get(key)?;
+        if entry.expires_at.is_some_and(|expires_at| expires_at <= Instant::now()) {
+            self.remove(key);
+            return None;
+        }
+        let old_tick = entry.tick;
+        self.order.remove(&old_tick);
+        let tick = self.touch(key);
+        let entry = self.entries.get_mut(key)?;
+        entry.tick = tick;
+        Some(entry.value.clone())
+    }
+
+    fn put(&mut self, key: &str, value: Vec<u8>, ttl: Option<Duration>, capacity: usize) {
+        self.remove(key);
+        while self.entries.len() >= capacity.max(1) {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        let tick = self.touch(key);
+        let expires_at = ttl.map(|ttl| Instant::now() + ttl);
+        self.entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                expires_at,
+                tick,
+            },
+        );
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.tick);
+        }
+    }
+}
+
+impl MemoryCacheBackend {
+    /// Create the backend, the capacity of the caches is read from the config
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            caches: Default::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCacheBackend {
+    async fn get(&self, cache: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        let mut caches = self.caches.lock().unwrap();
+        Ok(caches.get_mut(cache).and_then(|c| c.get(key)))
+    }
+
+    async fn put(&self, cache: &str, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        let capacity = self.config.max_capacity(cache);
+        let mut caches = self.caches.lock().unwrap();
+        caches
+            .entry(cache.to_string())
+            .or_default()
+            .put(key, value, ttl, capacity);
+        Ok(())
+    }
+
+    async fn evict(&self, cache: &str, key: &str) -> Result<()> {
+        if let Some(c) = self.caches.lock().unwrap().get_mut(cache) {
+            c.remove(key);
+        }
+        Ok(())
+    }
+
+    async fn clear(&self, cache: &str) -> Result<()> {
+        self.caches.lock().unwrap().remove(cache);
+        Ok(())
+    }
+}
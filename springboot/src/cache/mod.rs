@@ -0,0 +1,294 @@
+//! Declarative caching of service results.
+//!
+//! `#[cacheable]`, `#[cache_put]` and `#[cache_evict]` store the results of async functions
+//! in the [CacheManager] component, the in-memory backend is configured under `[cache]`:
+//!
+//! ```toml
+//! [cache]
+//! ttl = 600
+//! max_capacity = 10000
+//!
+//! [cache.caches.posts]
+//! ttl = 60
+//! ```
+pub mod config;
+mod memory;
+
+use crate::application::{App, AppBuilder};
+use crate::component::{ComponentRegistry, MutableComponentRegistry};
+use crate::config::ConfigRegistry;
+use crate::error::Result;
+use async_trait::async_trait;
+use config::CacheConfig;
+use dashmap::DashMap;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use memory::MemoryCacheBackend;
+pub use springboot_macros::{cache_evict, cache_put, cacheable};
+
+/// Storage of the cached values, values are serialized as json
+#[async_trait]
+pub trait CacheBackend: Send + Sync + 'static {
+    /// Get the value of the key
+    async fn get(&self, cache: &str, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Store the value of the key, it expires after `ttl` if given
+    async fn put(&self, cache: &str, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()>;
+
+    /// Remove the key
+    async fn evict(&self, cache: &str, key: &str) -> Result<()>;
+
+    /// Remove all keys of the cache
+    async fn clear(&self, cache: &str) -> Result<()>;
+}
+
+/// Hit and miss counters of a cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups that found a value
+    pub hits: u64,
+    /// Lookups that found nothing
+    pub misses: u64,
+    /// Values stored
+    pub puts: u64,
+    /// Keys or caches removed
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// Ratio of hits to lookups, `0.0` without lookups
+    pub fn hit_ratio(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    puts: AtomicU64,
+    evictions: AtomicU64,
+}
+
+/// Cache component used by the caching attributes
+#[derive(Clone)]
+pub struct CacheManager {
+    backend: Arc<dyn CacheBackend>,
+    config: Arc<CacheConfig>,
+    stats: Arc<DashMap<String, Counters>>,
+}
+
+impl CacheManager {
+    /// Create a manager storing the values in the given backend,
+    /// the time to live of the entries is read from the config
+    pub fn new<B: CacheBackend>(backend: B, config: CacheConfig) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            config: Arc::new(config),
+            stats: Default::default(),
+        }
+    }
+
+    /// The cache manager of the [current app](App::current), if there is one
+    pub fn current() -> Option<Self> {
+        App::try_current().ok()?.get_component::<Self>()
+    }
+
+    fn count(&self, cache: &str, counter: impl FnOnce(&Counters) -> &AtomicU64) {
+        let counters = self.stats.entry(cache.to_string()).or_default();
+        counter(&counters).fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the cached value of the key
+    pub async fn get<T: DeserializeOwned>(&self, cache: &str, key: &str) -> Result<Option<T>> {
+        let value = match self.backend.get(cache, key).await? {
+            Some(bytes) => Some(serde_json::from_slice(&bytes).map_err(anyhow::Error::from)?),
+            None => None,
+        };
+        match value {
+            Some(_) => self.count(cache, |c| &c.hits),
+            None => self.count(cache, |c| &c.misses),
+        }
+        Ok(value)
+    }
+
+    /// Store the value of the key
+    pub async fn put<T: Serialize>(&self, cache: &str, key: &str, value: &T) -> Result<()> {
+        let bytes = serde_json::to_vec(value).map_err(anyhow::Error::from)?;
+        self.backend
+            .put(cache, key, bytes, self.config.ttl(cache))
+            .await?;
+        self.count(cache, |c| &c.puts);
+        Ok(())
+    }
+
+    /// Remove the key
+    pub async fn evict(&self, cache: &str, key: &str) -> Result<()> {
+        self.backend.evict(cache, key).await?;
+        self.count(cache, |c| &c.evictions);
+        Ok(())
+    }
+
+    /// Remove all keys of the cache
+    pub async fn clear(&self, cache: &str) -> Result<()> {
+        self.backend.clear(cache).await?;
+        self.count(cache, |c| &c.evictions);
+        Ok(())
+    }
+
+    /// Statistics of the given cache
+    pub fn stats(&self, cache: &str) -> CacheStats {
+        self.stats
+            .get(cache)
+            .map(|counters| CacheStats {
+                hits: counters.hits.load(Ordering::Relaxed),
+                misses: counters.misses.load(Ordering::Relaxed),
+                puts: counters.puts.load(Ordering::Relaxed),
+                evictions: counters.evictions.load(Ordering::Relaxed),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Statistics of all caches used so far
+    pub fn all_stats(&self) -> Vec<(String, CacheStats)> {
+        let mut stats: Vec<_> = self
+            .stats
+            .iter()
+            .map(|entry| (entry.key().clone(), self.stats(entry.key())))
+            .collect();
+        stats.sort_by(|a, b| a.0.cmp(&b.0));
+        stats
+    }
+}
+
+/// Code generated by `#[cacheable]`: return the cached value or call the function and cache its result.
+/// `value` picks the value to cache from the result, `wrap` turns a cached value into a result.
+#[doc(hidden)]
+pub async fn cacheable<R, T, F>(
+    cache: &str,
+    key: String,
+    call: F,
+    value: fn(&R) -> Option<&T>,
+    wrap: fn(T) -> R,
+) -> R
+where
+    F: Future<Output = R>,
+    T: Serialize + DeserializeOwned,
+{
+    let Some(manager) = CacheManager::current() else {
+        return call.await;
+    };
+    match manager.get::<T>(cache, &key).await {
+        Ok(Some(cached)) => return wrap(cached),
+        Ok(None) => {}
+        Err(e) => log::warn!("cache {cache} lookup of {key} failed: {e:?}"),
+    }
+    let result = call.await;
+    if let Some(value) = value(&result) {
+        if let Err(e) = manager.put(cache, &key, value).await {
+            log::warn!("cache {cache} put of {key} failed: {e:?}");
+        }
+    }
+    result
+}
+
+/// Code generated by `#[cache_put]`: call the function and cache its result
+#[doc(hidden)]
+pub async fn cache_put<R, T, F>(cache: &str, key: String, call: F, value: fn(&R) -> Option<&T>) -> R
+where
+    F: Future<Output = R>,
+    T: Serialize,
+{
+    let result = call.await;
+    if let (Some(manager), Some(value)) = (CacheManager::current(), value(&result)) {
+        if let Err(e) = manager.put(cache, &key, value).await {
+            log::warn!("cache {cache} put of {key} failed: {e:?}");
+        }
+    }
+    result
+}
+
+/// Code generated by `#[cache_evict]`: call the function and remove the key, or all keys if `key` is `None`,
+/// unless the function failed
+#[doc(hidden)]
+pub async fn cache_evict<R, F>(cache: &str, key: Option<String>, call: F, succeeded: fn(&R) -> bool) -> R
+where
+    F: Future<Output = R>,
+{
+    let result = call.await;
+    if let (Some(manager), true) = (CacheManager::current(), succeeded(&result)) {
+        let evicted = match &key {
+            Some(key) => manager.evict(cache, key).await,
+            None => manager.clear(cache).await,
+        };
+        if let Err(e) = evicted {
+            log::warn!("cache {cache} eviction of {key:?} failed: {e:?}");
+        }
+    }
+    result
+}
+
+/// Register the in-memory [CacheManager] unless a starter added one with another backend
+pub(crate) fn add_cache_component(app: &mut AppBuilder) -> Result<()> {
+    if app.has_component::<CacheManager>() {
+        return Ok(());
+    }
+    let config = app.get_config::<CacheConfig>()?;
+    let backend = MemoryCacheBackend::new(config.clone());
+    app.add_component(CacheManager::new(backend, config));
+    Ok(())
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::config::{CacheConfig, CacheSpec};
+    use super::{CacheManager, MemoryCacheBackend};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_memory_cache() {
+        let mut config = CacheConfig::default();
+        let spec = CacheSpec {
+            ttl: None,
+            max_capacity: Some(2),
+        };
+        config.caches.insert("users".to_string(), spec);
+        let manager = CacheManager::new(MemoryCacheBackend::new(config.clone()), config);
+
+        manager.put("users", "1", &"alice").await.unwrap();
+        manager.put("users", "2", &"bob").await.unwrap();
+        // touch 1, so 2 is the least recently used
+        assert_eq!(manager.get::<String>("users", "1").await.unwrap().unwrap(), "alice");
+        manager.put("users", "3", &"carol").await.unwrap();
+        assert!(manager.get::<String>("users", "2").await.unwrap().is_none());
+        assert!(manager.get::<String>("users", "3").await.unwrap().is_some());
+
+        manager.evict("users", "3").await.unwrap();
+        assert!(manager.get::<String>("users", "3").await.unwrap().is_none());
+
+        let stats = manager.stats("users");
+        assert_eq!((stats.hits, stats.misses, stats.puts, stats.evictions), (2, 2, 3, 1));
+        assert_eq!(stats.hit_ratio(), 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_ttl() {
+        let config = CacheConfig::default();
+        let backend = MemoryCacheBackend::new(config.clone());
+        let mut short = config.clone();
+        short.ttl = Some(0);
+        let manager = CacheManager::new(backend, short);
+
+        manager.put("posts", "1", &1).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert!(manager.get::<i32>("posts", "1").await.unwrap().is_none());
+    }
+}
@@ -1,4 +1,13 @@
-use std::{any::Any, ops::Deref, sync::Arc};
+use dashmap::mapref::{
+    entry::Entry,
+    one::{MappedRefMut, RefMut},
+};
+use std::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
 
 /// Component's dyn trait reference
 #[derive(Debug, Clone)]
@@ -20,6 +29,19 @@ impl DynComponentRef {
     {
         self.0.downcast::<T>().ok().map(ComponentRef::new)
     }
+
+    /// Mutable access to the component.
+    /// If the component is shared by a [ComponentRef], it is cloned first and the reference keeps the old value.
+    pub(crate) fn make_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: Clone + Any + Send + Sync,
+    {
+        if Arc::get_mut(&mut self.0).is_none() {
+            let component = self.0.downcast_ref::<T>()?.clone();
+            self.0 = Arc::new(component);
+        }
+        Arc::get_mut(&mut self.0)?.downcast_mut::<T>()
+    }
 }
 
 /// A component reference of a specified type
@@ -45,3 +67,141 @@ impl<T> Deref for ComponentRef<T> {
         &self.0
     }
 }
+
+/// A component qualified by the marker type `N`, so several components of type `T` can be registered.
+/// ```
+/// use springboot::component::component::Named;
+///
+/// #[derive(Clone)]
+/// struct Pool(&'static str);
+///
+/// struct Primary;
+/// struct Replica;
+///
+/// let primary = Named::<Primary, _>::new(Pool("primary"));
+/// let replica = Named::<Replica, _>::new(Pool("replica"));
+/// assert_eq!((primary.0, replica.0), ("primary", "replica"));
+/// ```
+pub struct Named<N, T> {
+    component: T,
+    _name: PhantomData<fn() -> N>,
+}
+
+impl<N, T> Named<N, T> {
+    /// constructor
+    pub fn new(component: T) -> Self {
+        Self {
+            component,
+            _name: PhantomData,
+        }
+    }
+
+    /// Unwrap the component
+    pub fn into_inner(self) -> T {
+        self.component
+    }
+}
+
+impl<N, T: Clone> Clone for Named<N, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.component.clone())
+    }
+}
+
+impl<N, T: std::fmt::Debug> std::fmt::Debug for Named<N, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Named")
+            .field(&std::any::type_name::<N>())
+            .field(&self.component)
+            .finish()
+    }
+}
+
+impl<N, T> Deref for Named<N, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.component
+    }
+}
+
+/// A mutable borrow of a component in the registry of [AppBuilder](crate::application::AppBuilder).
+/// The registry stays locked for this component type until it is dropped.
+pub struct ComponentMut<'a, T>(Slot<'a, T>);
+
+enum Slot<'a, T> {
+    Registry(MappedRefMut<'a, TypeId, DynComponentRef, T>),
+    /// A value outside of the registry, the changes are discarded
+    Detached(Box<T>),
+}
+
+impl<'a, T> ComponentMut<'a, T>
+where
+    T: Clone + Any + Send + Sync,
+{
+    pub(crate) fn new(component: RefMut<'a, TypeId, DynComponentRef>) -> Option<Self> {
+        component
+            .try_map(|c| c.make_mut::<T>())
+            .ok()
+            .map(|component| Self(Slot::Registry(component)))
+    }
+}
+
+impl<T> Deref for ComponentMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            Slot::Registry(component) => component.value(),
+            Slot::Detached(component) => component,
+        }
+    }
+}
+
+impl<T> DerefMut for ComponentMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.0 {
+            Slot::Registry(component) => component.value_mut(),
+            Slot::Detached(component) => component,
+        }
+    }
+}
+
+/// A view into a component slot of the registry, see [`AppBuilder::component_entry`](crate::application::AppBuilder::component_entry)
+pub struct ComponentEntry<'a, T> {
+    /// `None` if the component is overridden
+    entry: Option<Entry<'a, TypeId, DynComponentRef>>,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<'a, T> ComponentEntry<'a, T>
+where
+    T: Clone + Any + Send + Sync,
+{
+    pub(crate) fn new(entry: Option<Entry<'a, TypeId, DynComponentRef>>) -> Self {
+        Self {
+            entry,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Insert the component returned by `f` if it is absent, and borrow it mutably.
+    /// An overridden component is not changed, the borrowed value is then `f()` and it is discarded.
+    pub fn or_insert_with<F: FnOnce() -> T>(self, f: F) -> ComponentMut<'a, T> {
+        let Some(entry) = self.entry else {
+            return ComponentMut(Slot::Detached(Box::new(f())));
+        };
+        let component = entry
+            .or_insert_with(|| DynComponentRef::new(f()))
+            .map(|c| c.make_mut::<T>().expect("component registered with a mismatched type"));
+        ComponentMut(Slot::Registry(component))
+    }
+
+    /// Insert the default component if it is absent, and borrow it mutably
+    pub fn or_default(self) -> ComponentMut<'a, T>
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+}
@@ -0,0 +1,182 @@
+//! Introspection of the registered starters and components,
+//! useful to find out why a component is missing or who provided it.
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Where a component came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
+pub enum ComponentOrigin {
+    /// Added by the [Starter](super::Starter) with the given name
+    Starter(String),
+    /// Built by the `Service` derive
+    Service,
+    /// Added directly on the [AppBuilder](crate::application::AppBuilder)
+    Manual,
+    /// Replaced by [`AppBuilder::override_component`](crate::application::AppBuilder::override_component)
+    Override,
+}
+
+/// Description of a registered component
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentDefinition {
+    /// Type name of the component
+    pub type_name: &'static str,
+    /// Where the component came from
+    pub origin: ComponentOrigin,
+    /// Prefixes of the configuration read to build the component
+    pub config_prefixes: Vec<&'static str>,
+    /// Type names of the components it depends on
+    pub dependencies: Vec<&'static str>,
+}
+
+impl ComponentDefinition {
+    pub(crate) fn new(type_name: &'static str, origin: ComponentOrigin) -> Self {
+        Self {
+            type_name,
+            origin,
+            config_prefixes: vec![],
+            dependencies: vec![],
+        }
+    }
+}
+
+/// Description of a registered starter
+#[derive(Debug, Clone, Serialize)]
+pub struct StarterDefinition {
+    /// Name of the starter
+    pub name: String,
+    /// Names of the starters it depends on
+    pub dependencies: Vec<String>,
+}
+
+/// Snapshot of the starters and components of an app
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ComponentGraph {
+    /// Registered starters, in build order
+    pub starters: Vec<StarterDefinition>,
+    /// Registered components, in registration order
+    pub components: Vec<ComponentDefinition>,
+}
+
+impl ComponentGraph {
+    /// Find the component with the given type name
+    pub fn component(&self, type_name: &str) -> Option<&ComponentDefinition> {
+        self.components.iter().find(|c| c.type_name == type_name)
+    }
+
+    /// Dependencies of components that are not registered
+    pub fn missing_dependencies(&self) -> Vec<(&'static str, &'static str)> {
+        self.components
+            .iter()
+            .flat_map(|c| c.dependencies.iter().map(move |dep| (c.type_name, *dep)))
+            .filter(|(_, dep)| self.component(dep).is_none())
+            .collect()
+    }
+
+    /// Export as pretty printed json
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("component graph is always serializable")
+    }
+
+    /// Export in the graphviz DOT format.
+    /// Missing dependencies are drawn as dashed red nodes.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph components {\n    rankdir=LR;\n");
+        for starter in &self.starters {
+            let _ = writeln!(dot, "    {:?} [shape=box, style=filled, fillcolor=lightgrey];", starter.name);
+            for dep in &starter.dependencies {
+                let _ = writeln!(dot, "    {:?} -> {:?} [style=dotted];", starter.name, dep);
+            }
+        }
+        for component in &self.components {
+            let mut label = component.type_name.to_string();
+            for prefix in &component.config_prefixes {
+                let _ = write!(label, "\n[{prefix}]");
+            }
+            let _ = writeln!(dot, "    {:?} [label={:?}];", component.type_name, label);
+            match &component.origin {
+                ComponentOrigin::Starter(name) => {
+                    let _ = writeln!(dot, "    {:?} -> {:?} [label=\"provides\"];", name, component.type_name);
+                }
+                ComponentOrigin::Override => {
+                    let _ = writeln!(dot, "    {:?} [color=orange];", component.type_name);
+                }
+                ComponentOrigin::Service | ComponentOrigin::Manual => {}
+            }
+            for dep in &component.dependencies {
+                let _ = writeln!(dot, "    {:?} -> {:?};", component.type_name, dep);
+            }
+        }
+        for (_, dep) in self.missing_dependencies() {
+            let _ = writeln!(dot, "    {:?} [style=dashed, color=red];", dep);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{ComponentGraph, ComponentOrigin};
+    use crate::application::AppBuilder;
+    use crate::component::{MutableComponentRegistry, Starter};
+    use crate::App;
+
+    #[tokio::test]
+    async fn test_component_graph() {
+        #[derive(Clone)]
+        struct Pool;
+
+        #[derive(Clone)]
+        struct UserService;
+
+        #[derive(Clone)]
+        struct Clock;
+
+        struct PoolStarter;
+
+        impl Starter for PoolStarter {
+            fn build(&self, app: &mut AppBuilder) {
+                app.add_component(Pool);
+            }
+
+            fn name(&self) -> &str {
+                "PoolStarter"
+            }
+        }
+
+        let mut builder = App::new();
+        builder
+            .use_config_str("")
+            .add_starter(PoolStarter)
+            .add_component(Clock)
+            .describe_component::<UserService>(
+                ComponentOrigin::Service,
+                vec!["user"],
+                vec![std::any::type_name::<Pool>(), "Mailer"],
+            );
+        let app = builder.build().await.expect("app build failed");
+        let graph = app.component_graph();
+
+        let pool = graph.component(std::any::type_name::<Pool>()).unwrap();
+        assert_eq!(pool.origin, ComponentOrigin::Starter("PoolStarter".to_string()));
+        let clock = graph.component(std::any::type_name::<Clock>()).unwrap();
+        assert_eq!(clock.origin, ComponentOrigin::Manual);
+        assert_eq!(
+            graph.missing_dependencies(),
+            [(std::any::type_name::<UserService>(), "Mailer")]
+        );
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"PoolStarter\" -> "));
+        assert!(dot.contains("\"Mailer\" [style=dashed, color=red];"));
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+        assert_eq!(json["starters"][0]["name"], "PoolStarter");
+        let components = json["components"].as_array().unwrap();
+        assert!(components
+            .iter()
+            .any(|c| c["origin"] == serde_json::json!({"kind": "starter", "name": "PoolStarter"})));
+    }
+}
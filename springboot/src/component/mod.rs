@@ -1,9 +1,16 @@
 // #![doc = include_str!("../../Plugin.md")]
 
 /// Component definition
+#[allow(clippy::module_inception)]
 pub mod component;
+pub mod graph;
 pub mod service;
 
+pub use inventory::submit;
+pub use springboot_macros::starter;
+
+use crate::config::spring::SpringConfig;
+use crate::config::ConfigRegistry;
 use crate::error::Result;
 use crate::{application::AppBuilder, error::AppError};
 use async_trait::async_trait;
@@ -46,6 +53,40 @@ pub trait Starter: Any + Send + Sync {
     }
 }
 
+/// Install a starter discovered through the `#[starter]` attribute
+pub trait StarterRegistrar: Send + Sync + 'static {
+    /// Type name of the starter, matched against `spring.autoconfigure.exclude`
+    fn name(&self) -> &'static str;
+
+    /// Add the starter to the app unless one of the same type was added explicitly
+    fn install_starter(&self, app: &mut AppBuilder);
+}
+
+inventory::collect!(&'static dyn StarterRegistrar);
+
+/// Register a [StarterRegistrar], used by the `#[starter]` macro
+#[macro_export]
+macro_rules! submit_starter {
+    ($ty:ident) => {
+        ::springboot::component::submit! {
+            &$ty as &dyn ::springboot::component::StarterRegistrar
+        }
+    };
+}
+
+/// Find all StarterRegistrar and install them into the app, except the excluded ones
+pub fn auto_discover_starters(app: &mut AppBuilder) -> Result<()> {
+    let config = app.get_config::<SpringConfig>()?.autoconfigure;
+    for registrar in inventory::iter::<&dyn StarterRegistrar> {
+        if config.is_excluded(registrar.name()) {
+            log::debug!("starter {} is excluded", registrar.name());
+            continue;
+        }
+        registrar.install_starter(app);
+    }
+    Ok(())
+}
+
 impl PluginRef {
     pub(crate) fn new<T: Starter>(plugin: T) -> Self {
         Self(Arc::new(plugin))
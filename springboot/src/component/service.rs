@@ -3,7 +3,9 @@
 use crate::application::AppBuilder;
 use crate::component::ComponentRegistry;
 use crate::config::ConfigRegistry;
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::timeline::StartupPhase;
+use std::collections::HashMap;
 
 pub use inventory::submit;
 
@@ -11,8 +13,10 @@ pub use springboot_macros::Service;
 
 /// Service is a special Component that can inject dependent Components as field members
 /// ```rust
-/// use spring::plugin::service::Service;
-/// use spring_sqlx::ConnectPool;
+/// use springboot::component::service::Service;
+///
+/// #[derive(Clone)]
+/// struct ConnectPool;
 ///
 /// #[derive(Clone, Service)]
 /// struct UserService {
@@ -32,6 +36,16 @@ pub trait Service: Clone + Sized + 'static {
 pub trait ServiceRegistrar: Send + Sync + 'static {
     /// Install the Service component into the App
     fn install_service(&self, app: &mut AppBuilder) -> Result<()>;
+
+    /// Name of the service, shown in the startup timeline
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Type names of the components injected into the service
+    fn dependencies(&self) -> Vec<&'static str> {
+        vec![]
+    }
 }
 
 inventory::collect!(&'static dyn ServiceRegistrar);
@@ -46,10 +60,108 @@ macro_rules! submit_service {
     };
 }
 
-/// Find all ServiceRegistrar and install them into the app
+/// Find all ServiceRegistrar and install them into the app,
+/// the services injected by a service are installed before it
 pub fn auto_inject_service(app: &mut AppBuilder) -> Result<()> {
-    for registrar in inventory::iter::<&dyn ServiceRegistrar> {
-        registrar.install_service(app)?;
+    let timeline = app.timeline.clone();
+    let registrars: Vec<&dyn ServiceRegistrar> = inventory::iter::<&dyn ServiceRegistrar>.into_iter().copied().collect();
+    for registrar in install_order(&registrars)? {
+        timeline
+            .time(StartupPhase::Service, registrar.name(), || registrar.install_service(app))
+            .map_err(|e| match e {
+                AppError::ComponentNotExist(component) => AppError::MissingDependency(registrar.name(), component),
+                e => e,
+            })?;
     }
     Ok(())
 }
+
+/// Order the services so that the services they inject come first
+fn install_order<'a>(registrars: &[&'a dyn ServiceRegistrar]) -> Result<Vec<&'a dyn ServiceRegistrar>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Visiting,
+        Done,
+    }
+
+    fn visit<'a>(
+        index: usize,
+        registrars: &[&'a dyn ServiceRegistrar],
+        marks: &mut HashMap<usize, Mark>,
+        path: &mut Vec<&'static str>,
+        order: &mut Vec<&'a dyn ServiceRegistrar>,
+    ) -> Result<()> {
+        let registrar = registrars[index];
+        match marks.get(&index) {
+            Some(Mark::Done) => return Ok(()),
+            Some(Mark::Visiting) => {
+                let start = path.iter().position(|name| *name == registrar.name()).unwrap_or(0);
+                let mut cycle = path[start..].to_vec();
+                cycle.push(registrar.name());
+                return Err(AppError::CyclicDependency(cycle.join(" -> ")));
+            }
+            None => {}
+        }
+        marks.insert(index, Mark::Visiting);
+        path.push(registrar.name());
+        for dependency in registrar.dependencies() {
+            // the other dependencies are components added by the starters or by hand
+            if let Some(dependency) = registrars.iter().position(|r| r.name() == dependency) {
+                visit(dependency, registrars, marks, path, order)?;
+            }
+        }
+        path.pop();
+        marks.insert(index, Mark::Done);
+        order.push(registrar);
+        Ok(())
+    }
+
+    let mut marks = HashMap::new();
+    let mut order = Vec::with_capacity(registrars.len());
+    for index in 0..registrars.len() {
+        visit(index, registrars, &mut marks, &mut vec![], &mut order)?;
+    }
+    Ok(order)
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{install_order, ServiceRegistrar};
+    use crate::application::AppBuilder;
+    use crate::error::{AppError, Result};
+
+    #[test]
+    fn test_install_order() {
+        struct Fake(&'static str, Vec<&'static str>);
+
+        impl ServiceRegistrar for Fake {
+            fn install_service(&self, _app: &mut AppBuilder) -> Result<()> {
+                Ok(())
+            }
+
+            fn name(&self) -> &'static str {
+                self.0
+            }
+
+            fn dependencies(&self) -> Vec<&'static str> {
+                self.1.clone()
+            }
+        }
+
+        let controller = Fake("Controller", vec!["BlogService", "Config"]);
+        let blog = Fake("BlogService", vec!["MongoService"]);
+        let mongo = Fake("MongoService", vec!["Pool"]);
+        let registrars: Vec<&dyn ServiceRegistrar> = vec![&controller, &blog, &mongo];
+        let order: Vec<&str> = install_order(&registrars).unwrap().iter().map(|r| r.name()).collect();
+        assert_eq!(order, ["MongoService", "BlogService", "Controller"]);
+
+        let a = Fake("A", vec!["B"]);
+        let b = Fake("B", vec!["C"]);
+        let c = Fake("C", vec!["A"]);
+        let registrars: Vec<&dyn ServiceRegistrar> = vec![&a, &b, &c];
+        match install_order(&registrars) {
+            Err(AppError::CyclicDependency(cycle)) => assert_eq!(cycle, "A -> B -> C -> A"),
+            other => panic!("expected a cycle, got {:?}", other.map(|_| ())),
+        }
+    }
+}
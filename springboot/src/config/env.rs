@@ -46,7 +46,7 @@ impl Env {
     pub fn from_string<S: Into<String>>(str: S) -> Self {
         match str.into() {
             s if s.eq_ignore_ascii_case("dev") => Self::Dev,
-            s if s.eq_ignore_ascii_case("tests") => Self::Test,
+            s if s.eq_ignore_ascii_case("test") || s.eq_ignore_ascii_case("tests") => Self::Test,
             s if s.eq_ignore_ascii_case("prod") => Self::Prod,
             _ => Self::Dev,
         }
@@ -2,6 +2,8 @@
 //!
 /// Environment Configuration
 pub mod env;
+/// Settings of the framework itself
+pub mod spring;
 /// Implement reading toml configuration
 pub mod toml;
 
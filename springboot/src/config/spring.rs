@@ -0,0 +1,121 @@
+use super::Configurable;
+use serde::Deserialize;
+
+/// Settings of the framework itself, read from the `[spring]` table
+#[derive(Debug, Default, Deserialize)]
+pub struct SpringConfig {
+    #[serde(default)]
+    pub application: ApplicationConfig,
+    #[serde(default)]
+    pub banner: BannerConfig,
+    #[serde(default)]
+    pub autoconfigure: AutoConfigureConfig,
+    #[serde(default)]
+    pub startup: StartupConfig,
+}
+
+impl Configurable for SpringConfig {
+    fn config_prefix() -> &'static str {
+        "spring"
+    }
+}
+
+/// `[spring.application]`
+#[derive(Debug, Default, Deserialize)]
+pub struct ApplicationConfig {
+    /// Name of the application
+    pub name: Option<String>,
+    /// Version of the application
+    pub version: Option<String>,
+}
+
+/// `[spring.banner]`
+#[derive(Debug, Deserialize)]
+pub struct BannerConfig {
+    /// Where the banner goes
+    #[serde(default)]
+    pub mode: BannerMode,
+    /// Path of the banner text file, the built-in banner is used if it doesn't exist
+    #[serde(default = "default_banner_location")]
+    pub location: String,
+}
+
+impl Default for BannerConfig {
+    fn default() -> Self {
+        Self {
+            mode: BannerMode::default(),
+            location: default_banner_location(),
+        }
+    }
+}
+
+fn default_banner_location() -> String {
+    "./config/banner.txt".to_string()
+}
+
+/// `spring.banner.mode`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BannerMode {
+    /// Print to the standard output
+    #[default]
+    Console,
+    /// Write to the log
+    Log,
+    /// Don't print the banner
+    Off,
+}
+
+/// `[spring.autoconfigure]`
+#[derive(Debug, Default, Deserialize)]
+pub struct AutoConfigureConfig {
+    /// Starters discovered through `#[starter]` that are not installed,
+    /// either by their full type name or by the type name without the module path.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// `[spring.startup]`
+#[derive(Debug, Default, Deserialize)]
+pub struct StartupConfig {
+    /// Print the [startup timeline](crate::timeline::StartupTimeline) once the app is started
+    #[serde(default)]
+    pub timeline: bool,
+}
+
+impl AutoConfigureConfig {
+    /// Whether the starter with the given name is excluded
+    pub fn is_excluded(&self, name: &str) -> bool {
+        let short_name = name.rsplit("::").next().unwrap_or(name);
+        self.exclude
+            .iter()
+            .any(|excluded| excluded == name || excluded == short_name)
+    }
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::SpringConfig;
+    use crate::config::toml::TomlConfigRegistry;
+    use crate::config::ConfigRegistry;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_autoconfigure_exclude() {
+        let registry = TomlConfigRegistry::from_str(
+            r#"
+            [spring.autoconfigure]
+            exclude = ["springboot_web::WebStarter", "LoggerStarter"]
+            "#,
+        )
+        .unwrap();
+        let config = registry.get_config::<SpringConfig>().unwrap().autoconfigure;
+
+        assert!(config.is_excluded("springboot_web::WebStarter"));
+        assert!(config.is_excluded("springboot_logger::LoggerStarter"));
+        assert!(!config.is_excluded("springboot_web::OtherStarter"));
+
+        let empty = TomlConfigRegistry::from_str("").unwrap();
+        assert!(empty.get_config::<SpringConfig>().unwrap().autoconfigure.exclude.is_empty());
+    }
+}
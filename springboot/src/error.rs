@@ -8,6 +8,18 @@ pub enum AppError {
     #[error("{0} component not exists")]
     ComponentNotExist(&'static str),
 
+    /// a service injects a component that is not registered
+    #[error("{0} injects {1}, which is not a component of the app")]
+    MissingDependency(&'static str, &'static str),
+
+    /// services inject each other
+    #[error("cyclic dependency between services: {0}")]
+    CyclicDependency(String),
+
+    /// no app is running in the current context
+    #[error("no app is running in the current context, the app is not built yet")]
+    AppNotBuilt,
+
     /// `.env` file reading failed
     #[error(transparent)]
     EnvError(#[from] dotenvy::Error),
@@ -24,6 +36,18 @@ pub enum AppError {
     #[error("merge toml error: {0}")]
     TomlMergeError(String),
 
+    /// the executor pool is full or shutting down
+    #[error("task rejected by the executor pool \"{0}\"")]
+    TaskRejected(String),
+
+    /// the circuit breaker is open
+    #[error("call not permitted, the circuit breaker \"{0}\" is open")]
+    CallNotPermitted(String),
+
+    /// the bulkhead has no free slot
+    #[error("call rejected, the bulkhead \"{0}\" is full")]
+    BulkheadFull(String),
+
     /// tokio asynchronous task join failed
     #[error(transparent)]
     JoinError(#[from] tokio::task::JoinError),
@@ -0,0 +1,181 @@
+use super::TaskExecutor;
+use crate::application::App;
+use crate::component::ComponentRegistry;
+use crate::error::{AppError, Result};
+use std::fmt::{self, Debug, Display};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+
+/// A method annotated with `#[async_method]`
+#[derive(Debug, Clone, Copy)]
+pub struct AsyncMethod {
+    /// Type name of the service
+    pub owner: &'static str,
+    /// Name of the method
+    pub name: &'static str,
+    /// Executor pool the method runs on
+    pub pool: &'static str,
+}
+
+impl Display for AsyncMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.owner, self.name)
+    }
+}
+
+/// Receives the errors of `#[async_method]` calls whose [AsyncResult] was dropped,
+/// see [`AppBuilder::uncaught_error_handler`](crate::application::AppBuilder::uncaught_error_handler)
+pub trait UncaughtErrorHandler: Send + Sync + 'static {
+    /// Handle the error returned by the method, or the failure to run it
+    fn handle(&self, method: &AsyncMethod, error: &dyn Debug);
+}
+
+impl<F> UncaughtErrorHandler for F
+where
+    F: Fn(&AsyncMethod, &dyn Debug) + Send + Sync + 'static,
+{
+    fn handle(&self, method: &AsyncMethod, error: &dyn Debug) {
+        self(method, error)
+    }
+}
+
+/// The default handler logs the error
+pub(crate) struct LogErrorHandler;
+
+impl UncaughtErrorHandler for LogErrorHandler {
+    fn handle(&self, method: &AsyncMethod, error: &dyn Debug) {
+        log::error!("async method {method} failed: {error:?}");
+    }
+}
+
+/// Return values supported by `#[async_method]`
+pub trait AsyncMethodOutput {
+    /// The error to report if nobody awaits the result
+    fn error(&self) -> Option<&dyn Debug>;
+}
+
+impl AsyncMethodOutput for () {
+    fn error(&self) -> Option<&dyn Debug> {
+        None
+    }
+}
+
+impl<T, E: Debug> AsyncMethodOutput for std::result::Result<T, E> {
+    fn error(&self) -> Option<&dyn Debug> {
+        self.as_ref().err().map(|e| e as &dyn Debug)
+    }
+}
+
+/// The pending result of an `#[async_method]` call.
+///
+/// The method keeps running if it is dropped, errors are then passed to the [UncaughtErrorHandler].
+pub struct AsyncResult<T>(oneshot::Receiver<Result<T>>);
+
+impl<T> Future for AsyncResult<T> {
+    type Output = Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx).map(|result| {
+            result.unwrap_or_else(|_| Err(anyhow::anyhow!("async method task was cancelled").into()))
+        })
+    }
+}
+
+/// Run the method body on its pool, used by the code generated by `#[async_method]`
+#[doc(hidden)]
+pub fn dispatch<F>(method: AsyncMethod, future: F) -> AsyncResult<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: AsyncMethodOutput + Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    let app = App::try_current();
+    let executor = app
+        .as_ref()
+        .map_err(|_| AppError::AppNotBuilt)
+        .and_then(|app| app.try_get_component::<TaskExecutor>());
+    let handler = executor
+        .as_ref()
+        .map(|executor| executor.inner.uncaught_error_handler.clone())
+        .unwrap_or_else(|_| Arc::new(LogErrorHandler));
+    let spawner = executor.as_ref().ok().cloned();
+
+    let task = async move {
+        let executor = executor?;
+        let pool = executor
+            .pool(method.pool)
+            .ok_or_else(|| AppError::TaskRejected(method.pool.to_string()))?;
+        match pool.spawn(future).await? {
+            Some(handle) => Ok(handle.await?),
+            None => Err(AppError::TaskRejected(method.pool.to_string())),
+        }
+    };
+    let report = async move {
+        let result: Result<F::Output> = task.await;
+        if let Err(result) = tx.send(result) {
+            match &result {
+                Err(e) => handler.handle(&method, e),
+                Ok(output) => {
+                    if let Some(e) = output.error() {
+                        handler.handle(&method, e)
+                    }
+                }
+            }
+        }
+    };
+    // the shutdown of the executor waits for the report, without an executor it fails at once
+    match spawner {
+        Some(executor) => executor.spawn_internal(report),
+        None => tokio::spawn(report),
+    };
+    AsyncResult(rx)
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{dispatch, AsyncMethod};
+    use crate::testing::TestApp;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::Notify;
+
+    #[tokio::test]
+    async fn test_dispatch() {
+        let errors = Arc::new(Mutex::new(vec![]));
+        let reported = errors.clone();
+        let handled = Arc::new(Notify::new());
+        let notify = handled.clone();
+        let app = TestApp::build(TestApp::builder("").uncaught_error_handler(
+            move |method: &AsyncMethod, error: &dyn std::fmt::Debug| {
+                reported.lock().unwrap().push(format!("{method}: {error:?}"));
+                notify.notify_one();
+            },
+        ))
+        .await
+        .unwrap();
+
+        let method = AsyncMethod {
+            owner: "MailService",
+            name: "send_mail",
+            pool: "default",
+        };
+        let sent = dispatch(method, async { Ok::<_, String>(3) }).await.unwrap();
+        assert_eq!(sent, Ok(3));
+
+        drop(dispatch(method, async { Err::<(), _>("smtp down") }));
+        let unknown_pool = AsyncMethod { pool: "unknown", ..method };
+        assert!(dispatch(unknown_pool, async {}).await.is_err());
+
+        tokio::time::timeout(Duration::from_secs(5), handled.notified())
+            .await
+            .expect("the error is reported");
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec!["MailService::send_mail: \"smtp down\"".to_string()]
+        );
+        app.shutdown().await.unwrap();
+    }
+}
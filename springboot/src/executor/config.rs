@@ -0,0 +1,78 @@
+use crate::config::Configurable;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// `[executor]`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecutorConfig {
+    /// Seconds to wait for running tasks on shutdown
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+    /// Pools by name, `default` is used by [`TaskExecutor::spawn`](super::TaskExecutor::spawn)
+    #[serde(default)]
+    pub pools: HashMap<String, PoolConfig>,
+}
+
+impl Configurable for ExecutorConfig {
+    fn config_prefix() -> &'static str {
+        "executor"
+    }
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self {
+            shutdown_timeout: default_shutdown_timeout(),
+            pools: Default::default(),
+        }
+    }
+}
+
+fn default_shutdown_timeout() -> u64 {
+    30
+}
+
+/// `[executor.pools.<name>]`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PoolConfig {
+    /// Tasks running at the same time
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
+    /// Tasks waiting for a free slot, more tasks are rejected
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    /// What happens to a task submitted to a full queue
+    #[serde(default)]
+    pub rejection: RejectionPolicy,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: default_max_concurrency(),
+            queue_capacity: default_queue_capacity(),
+            rejection: RejectionPolicy::default(),
+        }
+    }
+}
+
+fn default_max_concurrency() -> usize {
+    64
+}
+
+fn default_queue_capacity() -> usize {
+    1024
+}
+
+/// Policy for tasks submitted to a full pool
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RejectionPolicy {
+    /// Wait until the queue has room
+    #[default]
+    Block,
+    /// Discard the task
+    Drop,
+    /// Fail with [`AppError::TaskRejected`](crate::error::AppError::TaskRejected)
+    Error,
+}
@@ -0,0 +1,367 @@
+//! Managed pools for background tasks.
+//!
+//! Tasks spawned through the [TaskExecutor] component are bounded by the pool configuration,
+//! run in the current app and tracing span, and are awaited on shutdown.
+//!
+//! ```toml
+//! [executor]
+//! shutdown_timeout = 30
+//!
+//! [executor.pools.mail]
+//! max_concurrency = 4
+//! queue_capacity = 100
+//! rejection = "error"
+//! ```
+mod async_method;
+pub mod config;
+
+pub use async_method::{dispatch, AsyncMethod, AsyncMethodOutput, AsyncResult, UncaughtErrorHandler};
+pub use springboot_macros::async_method;
+
+use crate::application::{App, AppBuilder};
+use crate::component::{ComponentRegistry, MutableComponentRegistry};
+use crate::config::ConfigRegistry;
+use crate::error::{AppError, Result};
+use async_method::LogErrorHandler;
+use config::{ExecutorConfig, PoolConfig, RejectionPolicy};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, Semaphore};
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+/// Name of the pool used by [`TaskExecutor::spawn`]
+pub const DEFAULT_POOL: &str = "default";
+
+/// Task executor component with named pools configured under `[executor]`
+#[derive(Clone)]
+pub struct TaskExecutor {
+    inner: Arc<ExecutorInner>,
+}
+
+struct ExecutorInner {
+    pools: HashMap<String, TaskPool>,
+    shutdown_timeout: Duration,
+    tracker: TaskTracker,
+    uncaught_error_handler: Arc<dyn UncaughtErrorHandler>,
+}
+
+/// A pool of the [TaskExecutor]
+#[derive(Clone)]
+pub struct TaskPool {
+    name: Arc<str>,
+    rejection: RejectionPolicy,
+    /// running and queued tasks
+    slots: Arc<Semaphore>,
+    /// running tasks
+    running: Arc<Semaphore>,
+    tracker: TaskTracker,
+}
+
+/// Counts the tasks that are not finished yet
+#[derive(Clone, Default)]
+struct TaskTracker(Arc<TrackerState>);
+
+#[derive(Default)]
+struct TrackerState {
+    active: AtomicUsize,
+    closed: AtomicBool,
+    idle: Notify,
+}
+
+struct TrackedTask(TaskTracker);
+
+impl Drop for TrackedTask {
+    fn drop(&mut self) {
+        if self.0 .0.active.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0 .0.idle.notify_waiters();
+        }
+    }
+}
+
+impl TaskTracker {
+    fn track(&self) -> Option<TrackedTask> {
+        if self.0.closed.load(Ordering::Acquire) {
+            return None;
+        }
+        self.0.active.fetch_add(1, Ordering::AcqRel);
+        Some(TrackedTask(self.clone()))
+    }
+
+    async fn wait_idle(&self) {
+        loop {
+            let idle = self.0.idle.notified();
+            tokio::pin!(idle);
+            idle.as_mut().enable();
+            if self.0.active.load(Ordering::Acquire) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+impl TaskExecutor {
+    /// Create the executor with the given configuration, the `default` pool always exists
+    pub fn new(config: ExecutorConfig) -> Self {
+        Self::with_uncaught_error_handler(config, Arc::new(LogErrorHandler))
+    }
+
+    /// Create the executor with a handler for the errors of unobserved `#[async_method]` calls
+    pub fn with_uncaught_error_handler(
+        config: ExecutorConfig,
+        uncaught_error_handler: Arc<dyn UncaughtErrorHandler>,
+    ) -> Self {
+        let tracker = TaskTracker::default();
+        let mut pools: HashMap<String, TaskPool> = config
+            .pools
+            .into_iter()
+            .map(|(name, pool)| {
+                let pool = TaskPool::new(&name, pool, tracker.clone());
+                (name, pool)
+            })
+            .collect();
+        pools
+            .entry(DEFAULT_POOL.to_string())
+            .or_insert_with(|| TaskPool::new(DEFAULT_POOL, PoolConfig::default(), tracker.clone()));
+        Self {
+            inner: Arc::new(ExecutorInner {
+                pools,
+                shutdown_timeout: Duration::from_secs(config.shutdown_timeout),
+                tracker,
+                uncaught_error_handler,
+            }),
+        }
+    }
+
+    /// Get the pool with the given name
+    pub fn pool(&self, name: &str) -> Option<&TaskPool> {
+        self.inner.pools.get(name)
+    }
+
+    fn default_pool(&self) -> &TaskPool {
+        &self.inner.pools[DEFAULT_POOL]
+    }
+
+    /// Spawn the future on the `default` pool, see [`TaskPool::spawn`]
+    pub async fn spawn<F>(&self, future: F) -> Result<Option<JoinHandle<F::Output>>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.default_pool().spawn(future).await
+    }
+
+    /// Run the blocking function on the `default` pool, see [`TaskPool::spawn_blocking`]
+    pub async fn spawn_blocking<F, T>(&self, f: F) -> Result<Option<JoinHandle<T>>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.default_pool().spawn_blocking(f).await
+    }
+
+    /// Spawn a task of the executor itself, it is awaited on shutdown but bounded by no pool
+    pub(crate) fn spawn_internal<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        // after shutdown started, the task only reports the rejection of its work
+        let tracked = self.inner.tracker.track();
+        let task = async move {
+            let output = future.await;
+            drop(tracked);
+            output
+        };
+        match App::try_current() {
+            Ok(app) => tokio::spawn(App::scope(app, task)),
+            Err(_) => tokio::spawn(task),
+        }
+    }
+
+    /// Number of spawned tasks that are not finished yet
+    pub fn active_tasks(&self) -> usize {
+        self.inner.tracker.0.active.load(Ordering::Acquire)
+    }
+
+    /// Reject new tasks and wait for the spawned ones until the shutdown timeout elapses.
+    /// Returns `false` if some tasks were still running after the timeout.
+    pub async fn shutdown(&self) -> bool {
+        self.inner.tracker.0.closed.store(true, Ordering::Release);
+        tokio::time::timeout(self.inner.shutdown_timeout, self.inner.tracker.wait_idle())
+            .await
+            .is_ok()
+    }
+}
+
+impl TaskPool {
+    fn new(name: &str, config: PoolConfig, tracker: TaskTracker) -> Self {
+        let max_concurrency = config.max_concurrency.max(1);
+        Self {
+            name: name.into(),
+            rejection: config.rejection,
+            slots: Arc::new(Semaphore::new(max_concurrency + config.queue_capacity)),
+            running: Arc::new(Semaphore::new(max_concurrency)),
+            tracker,
+        }
+    }
+
+    /// Spawn the future in the current tracing span and [current app](App::current).
+    ///
+    /// If the pool is full, the [RejectionPolicy] decides:
+    /// `block` waits for room, `drop` returns `Ok(None)` and `error` returns [AppError::TaskRejected].
+    /// After shutdown started, tasks are always rejected with an error.
+    pub async fn spawn<F>(&self, future: F) -> Result<Option<JoinHandle<F::Output>>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let Some(slot) = self.acquire_slot().await? else {
+            return Ok(None);
+        };
+        let tracked = self.tracker.track().ok_or_else(|| self.rejected())?;
+        let running = self.running.clone();
+        let future = future.instrument(tracing::Span::current());
+        let task = async move {
+            let _permit = running.acquire_owned().await;
+            let output = future.await;
+            drop((slot, tracked));
+            output
+        };
+        Ok(Some(match App::try_current() {
+            Ok(app) => tokio::spawn(App::scope(app, task)),
+            Err(_) => tokio::spawn(task),
+        }))
+    }
+
+    /// Run the blocking function on the blocking thread pool of tokio,
+    /// bounded and rejected like [`TaskPool::spawn`].
+    pub async fn spawn_blocking<F, T>(&self, f: F) -> Result<Option<JoinHandle<T>>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let span = tracing::Span::current();
+        let app = App::try_current().ok();
+        self.spawn(async move {
+            let blocking = tokio::task::spawn_blocking(move || {
+                let previous = App::set_scoped(app);
+                let output = span.in_scope(f);
+                App::set_scoped(previous);
+                output
+            });
+            match blocking.await {
+                Ok(output) => output,
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            }
+        })
+        .await
+    }
+
+    async fn acquire_slot(&self) -> Result<Option<tokio::sync::OwnedSemaphorePermit>> {
+        match self.rejection {
+            RejectionPolicy::Block => {
+                let slot = self.slots.clone().acquire_owned().await;
+                Ok(Some(slot.map_err(|_| self.rejected())?))
+            }
+            RejectionPolicy::Drop => match self.slots.clone().try_acquire_owned() {
+                Ok(slot) => Ok(Some(slot)),
+                Err(_) => {
+                    log::warn!("executor pool {} is full, the task is dropped", self.name);
+                    Ok(None)
+                }
+            },
+            RejectionPolicy::Error => {
+                let slot = self.slots.clone().try_acquire_owned();
+                Ok(Some(slot.map_err(|_| self.rejected())?))
+            }
+        }
+    }
+
+    fn rejected(&self) -> AppError {
+        AppError::TaskRejected(self.name.to_string())
+    }
+}
+
+/// Register the [TaskExecutor] component once the starters are built,
+/// so its shutdown hook drains the tasks before the hooks of the starters run
+pub(crate) fn add_executor_component(app: &mut AppBuilder) -> Result<()> {
+    if app.has_component::<TaskExecutor>() {
+        return Ok(());
+    }
+    let config = app.get_config::<ExecutorConfig>()?;
+    let executor = match app.uncaught_error_handler.clone() {
+        Some(handler) => TaskExecutor::with_uncaught_error_handler(config, handler),
+        None => TaskExecutor::new(config),
+    };
+    app.add_component(executor.clone());
+    app.add_shutdown_hook(move |_| {
+        Box::new(async move {
+            if executor.shutdown().await {
+                Ok("task executor finished".to_string())
+            } else {
+                Ok(format!(
+                    "task executor timed out with {} running tasks",
+                    executor.active_tasks()
+                ))
+            }
+        })
+    });
+    Ok(())
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::config::{ExecutorConfig, PoolConfig, RejectionPolicy};
+    use super::TaskExecutor;
+    use crate::error::AppError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::oneshot;
+
+    #[tokio::test]
+    async fn test_rejection_and_shutdown() {
+        let mut config = ExecutorConfig::default();
+        let pool = PoolConfig {
+            max_concurrency: 1,
+            queue_capacity: 1,
+            rejection: RejectionPolicy::Error,
+        };
+        config.pools.insert("mail".to_string(), pool);
+        let executor = TaskExecutor::new(config);
+        let mail = executor.pool("mail").unwrap();
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let finished = Arc::new(AtomicUsize::new(0));
+        let counter = finished.clone();
+        mail.spawn(async move {
+            let _ = rx.await;
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .await
+        .unwrap();
+        let counter = finished.clone();
+        mail.spawn(async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .await
+        .unwrap();
+
+        let rejected = mail.spawn(async {}).await;
+        assert!(matches!(rejected, Err(AppError::TaskRejected(name)) if name == "mail"));
+        assert_eq!(executor.active_tasks(), 2);
+
+        let blocking = executor.spawn_blocking(|| 42).await.unwrap().unwrap();
+        assert_eq!(blocking.await.unwrap(), 42);
+
+        tx.send(()).unwrap();
+        assert!(executor.shutdown().await);
+        assert_eq!(finished.load(Ordering::SeqCst), 2);
+        assert!(executor.spawn(async {}).await.is_err());
+    }
+}
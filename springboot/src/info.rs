@@ -0,0 +1,183 @@
+//! Information about the application build, see [app_info](crate::app_info).
+use crate::application::AppBuilder;
+use crate::component::{ComponentRegistry, MutableComponentRegistry};
+use crate::config::spring::SpringConfig;
+use crate::config::ConfigRegistry;
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// Name, version and build details of the application, a component of every app.
+///
+/// Created by the [app_info](crate::app_info) macro in the application crate and registered with
+/// [`AppBuilder::app_info`](crate::application::AppBuilder::app_info).
+/// Otherwise the app registers one with the name and version of `spring.application`,
+/// the name defaulting to the file name of the executable, and without build details.
+/// The git and toolchain details are only available if the build script calls [emit_build_info].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AppInfo {
+    /// Crate name of the application
+    pub name: Cow<'static, str>,
+    /// Crate version of the application
+    pub version: Cow<'static, str>,
+    /// Short hash of the commit the application was built from
+    pub git_commit: Option<&'static str>,
+    /// Branch the application was built from
+    pub git_branch: Option<&'static str>,
+    /// Build time in RFC 3339 format, UTC
+    pub build_timestamp: Option<&'static str>,
+    /// Version of the rust compiler
+    pub rustc_version: Option<&'static str>,
+    /// Cargo features enabled for the application crate
+    pub features: Vec<&'static str>,
+}
+
+/// Build an [AppInfo] from the package of the calling crate
+/// and the variables emitted by [emit_build_info](crate::info::emit_build_info).
+///
+/// ```
+/// let info = springboot::app_info!();
+/// assert_eq!(info.name, "springboot");
+/// ```
+#[macro_export]
+macro_rules! app_info {
+    () => {
+        $crate::info::AppInfo {
+            name: ::std::borrow::Cow::Borrowed(env!("CARGO_PKG_NAME")),
+            version: ::std::borrow::Cow::Borrowed(env!("CARGO_PKG_VERSION")),
+            git_commit: option_env!("SPRING_BUILD_GIT_COMMIT"),
+            git_branch: option_env!("SPRING_BUILD_GIT_BRANCH"),
+            build_timestamp: option_env!("SPRING_BUILD_TIMESTAMP"),
+            rustc_version: option_env!("SPRING_BUILD_RUSTC_VERSION"),
+            features: option_env!("SPRING_BUILD_FEATURES")
+                .map(|features| features.split(',').filter(|f| !f.is_empty()).collect())
+                .unwrap_or_default(),
+        }
+    };
+}
+
+/// Register the [AppInfo] of the configuration unless the application registered its own
+pub(crate) fn add_app_info_component(app: &mut AppBuilder) {
+    if app.has_component::<AppInfo>() {
+        return;
+    }
+    let config = app.get_config::<SpringConfig>().unwrap_or_default().application;
+    let name = config
+        .name
+        .or_else(|| {
+            let exe = std::env::current_exe().ok()?;
+            Some(exe.file_stem()?.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+    app.add_component(AppInfo {
+        name: name.into(),
+        version: config.version.unwrap_or_default().into(),
+        ..Default::default()
+    });
+}
+
+/// Emit the build details read by [app_info](crate::app_info),
+/// call it from the `main` of the `build.rs` with springboot in `[build-dependencies]`:
+///
+/// ```no_run
+/// springboot::info::emit_build_info();
+/// ```
+pub fn emit_build_info() {
+    use std::process::Command;
+
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    if let Some(commit) = git(&["rev-parse", "--short", "HEAD"]) {
+        println!("cargo:rustc-env=SPRING_BUILD_GIT_COMMIT={commit}");
+    }
+    if let Some(branch) = git(&["rev-parse", "--abbrev-ref", "HEAD"]) {
+        println!("cargo:rustc-env=SPRING_BUILD_GIT_BRANCH={branch}");
+    }
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        println!("cargo:rerun-if-changed={git_dir}/HEAD");
+        println!("cargo:rerun-if-changed={git_dir}/refs/heads");
+    }
+
+    // reproducible builds set SOURCE_DATE_EPOCH
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default()
+        });
+    println!("cargo:rustc-env=SPRING_BUILD_TIMESTAMP={}", format_rfc3339(timestamp));
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    if let Ok(output) = Command::new(rustc).arg("--version").output() {
+        let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        println!("cargo:rustc-env=SPRING_BUILD_RUSTC_VERSION={version}");
+    }
+
+    // CARGO_CFG_FEATURE has the exact names since Rust 1.80, the older CARGO_FEATURE_<NAME>
+    // variables are uppercased with `-` replaced by `_`, so `my_feature` is reported as `my-feature`
+    let mut features: Vec<String> = match std::env::var("CARGO_CFG_FEATURE") {
+        Ok(features) => features.split(',').filter(|f| !f.is_empty()).map(str::to_string).collect(),
+        Err(_) => std::env::vars()
+            .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(str::to_string))
+            .map(|feature| feature.to_lowercase().replace('_', "-"))
+            .collect(),
+    };
+    features.sort();
+    println!("cargo:rustc-env=SPRING_BUILD_FEATURES={}", features.join(","));
+}
+
+/// Format seconds since the unix epoch as `YYYY-MM-DDTHH:MM:SSZ`
+fn format_rfc3339(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let secs_of_day = secs % 86400;
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{format_rfc3339, AppInfo};
+    use crate::component::ComponentRegistry;
+    use crate::testing::TestApp;
+
+    #[tokio::test]
+    async fn test_default_app_info() {
+        let toml = "[spring.application]\nname = \"blog\"\nversion = \"1.2.0\"";
+        let app = TestApp::build(&mut TestApp::builder(toml)).await.unwrap();
+        let info = app.app().get_component::<AppInfo>().unwrap();
+        assert_eq!((info.name, info.version), ("blog".into(), "1.2.0".into()));
+
+        let app = TestApp::build(TestApp::builder(toml).app_info(crate::app_info!())).await.unwrap();
+        assert_eq!(app.app().get_component::<AppInfo>().unwrap().name, "springboot");
+    }
+
+    #[test]
+    fn test_format_rfc3339() {
+        assert_eq!(format_rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_rfc3339(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_rfc3339(1760875200), "2025-10-19T12:00:00Z");
+    }
+}
@@ -0,0 +1,245 @@
+//! Interceptors wrapping service methods annotated with `#[around(Interceptor)]`,
+//! for cross-cutting concerns such as timing, audit logging or authorization checks.
+use crate::application::{App, AppBuilder};
+use crate::component::ComponentRegistry;
+use crate::error::{AppError, Result};
+use std::any::Any;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+
+pub use inventory::submit;
+pub use springboot_macros::around;
+
+/// The intercepted method call
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    /// Type name of the service, or the module path of a free function
+    pub owner: &'static str,
+    /// Name of the method
+    pub method: &'static str,
+}
+
+/// Result of an intercepted method
+pub trait MethodResult: Send {
+    /// The error of the method, if it failed
+    fn error(&self) -> Option<&dyn Debug>;
+}
+
+impl<T: Send, E: Debug + Send> MethodResult for std::result::Result<T, E> {
+    fn error(&self) -> Option<&dyn Debug> {
+        self.as_ref().err().map(|e| e as &dyn Debug)
+    }
+}
+
+/// Arguments of an intercepted method, moved into the method when the call proceeds
+pub trait MethodArgs: Send {
+    /// The arguments in order
+    fn debug_args(&self) -> Vec<&dyn Debug>;
+}
+
+macro_rules! impl_method_args {
+    ($($ty:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($ty: Debug + Send,)*> MethodArgs for ($($ty,)*) {
+            fn debug_args(&self) -> Vec<&dyn Debug> {
+                let ($($ty,)*) = self;
+                vec![$($ty as &dyn Debug),*]
+            }
+        }
+    };
+}
+
+impl_method_args!();
+impl_method_args!(T1);
+impl_method_args!(T1, T2);
+impl_method_args!(T1, T2, T3);
+impl_method_args!(T1, T2, T3, T4);
+impl_method_args!(T1, T2, T3, T4, T5);
+impl_method_args!(T1, T2, T3, T4, T5, T6);
+impl_method_args!(T1, T2, T3, T4, T5, T6, T7);
+impl_method_args!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_method_args!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_method_args!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_method_args!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_method_args!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+
+trait Call<'a, R>: Send {
+    fn args(&self) -> Vec<&dyn Debug>;
+
+    fn call(self: Box<Self>) -> Pin<Box<dyn Future<Output = R> + Send + 'a>>;
+}
+
+struct MethodCall<A, F> {
+    args: A,
+    call: F,
+}
+
+impl<'a, A, F, Fut, R> Call<'a, R> for MethodCall<A, F>
+where
+    A: MethodArgs + 'a,
+    F: FnOnce(A) -> Fut + Send + 'a,
+    Fut: Future<Output = R> + Send + 'a,
+{
+    fn args(&self) -> Vec<&dyn Debug> {
+        self.args.debug_args()
+    }
+
+    fn call(self: Box<Self>) -> Pin<Box<dyn Future<Output = R> + Send + 'a>> {
+        Box::pin((self.call)(self.args))
+    }
+}
+
+/// The rest of the call, the next interceptor or the method itself
+pub struct Proceed<'a, R> {
+    arg_names: &'static [&'static str],
+    call: Box<dyn Call<'a, R> + 'a>,
+}
+
+impl<'a, R> Proceed<'a, R> {
+    /// Names and `Debug` of the arguments, nothing is formatted unless the interceptor does.
+    /// The arguments are moved into the method by [`Proceed::proceed`],
+    /// so an interceptor logging them after the call formats them before.
+    pub fn args(&self) -> Vec<(&'static str, &dyn Debug)> {
+        self.arg_names.iter().copied().zip(self.call.args()).collect()
+    }
+
+    /// Continue with the call
+    pub async fn proceed(self) -> R {
+        self.call.call().await
+    }
+}
+
+/// Wraps the calls of the methods annotated with `#[around(Self)]`.
+///
+/// The interceptor is looked up as a component of the current app, so it can be a `Service`.
+/// An app with the owner component of an `#[around]` method but without its interceptor fails to start.
+/// Returning an error without proceeding rejects the call, the error is converted into the error of the method.
+pub trait Interceptor: Clone + Send + Sync + 'static {
+    /// Intercept the invocation, call [`Proceed::proceed`] to run the method
+    fn around<R: MethodResult>(
+        &self,
+        invocation: &Invocation,
+        proceed: Proceed<'_, R>,
+    ) -> impl Future<Output = Result<R>> + Send;
+}
+
+/// An `#[around]` method, checked when the app starts
+#[doc(hidden)]
+pub struct Interception {
+    /// Path of the method
+    pub method: &'static str,
+    /// Type name of the interceptor
+    pub interceptor: fn() -> &'static str,
+    /// Whether the app has the owner component, the interceptor is only required then
+    pub has_owner: fn(&AppBuilder) -> bool,
+    /// Whether the app has the interceptor component
+    pub has_interceptor: fn(&AppBuilder) -> bool,
+}
+
+impl Interception {
+    /// Used for [`Interception::has_owner`] and [`Interception::has_interceptor`]
+    pub fn has_component<T: Any + Send + Sync>(app: &AppBuilder) -> bool {
+        app.has_component::<T>()
+    }
+}
+
+inventory::collect!(Interception);
+
+/// Check that the app has the interceptor components of the `#[around]` methods of its components
+pub(crate) fn check_interceptors(app: &AppBuilder) -> Result<()> {
+    let missing: Vec<String> = inventory::iter::<Interception>
+        .into_iter()
+        .filter(|interception| (interception.has_owner)(app) && !(interception.has_interceptor)(app))
+        .map(|interception| format!("`{}` required by `{}`", (interception.interceptor)(), interception.method))
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!("missing interceptor components: {}", missing.join(", ")).into());
+    }
+    Ok(())
+}
+
+/// Code generated by `#[around]`: run the call through the interceptor component
+#[doc(hidden)]
+pub async fn intercept<'a, I, T, E, A, F, Fut>(
+    invocation: Invocation,
+    arg_names: &'static [&'static str],
+    args: A,
+    call: F,
+) -> std::result::Result<T, E>
+where
+    I: Interceptor,
+    T: Send,
+    E: From<AppError> + Debug + Send,
+    A: MethodArgs + 'a,
+    F: FnOnce(A) -> Fut + Send + 'a,
+    Fut: Future<Output = std::result::Result<T, E>> + Send + 'a,
+{
+    let interceptor = App::try_current()?.try_get_component::<I>()?;
+    let proceed = Proceed {
+        arg_names,
+        call: Box::new(MethodCall { args, call }),
+    };
+    interceptor
+        .around(&invocation, proceed)
+        .await
+        .unwrap_or_else(|e| Err(e.into()))
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{intercept, Interceptor, Invocation, MethodResult, Proceed};
+    use crate::application::App;
+    use crate::component::MutableComponentRegistry;
+    use crate::error::{AppError, Result};
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_intercept() {
+        #[derive(Clone, Default)]
+        struct Audit(Arc<Mutex<Vec<String>>>);
+
+        impl Interceptor for Audit {
+            async fn around<R: MethodResult>(&self, invocation: &Invocation, proceed: Proceed<'_, R>) -> Result<R> {
+                let args = format!("{:?}", proceed.args());
+                if args.contains("\"root\"") {
+                    return Err(AppError::OtherError(anyhow::anyhow!("forbidden")));
+                }
+                let result = proceed.proceed().await;
+                let outcome = match result.error() {
+                    Some(e) => format!("{e:?}"),
+                    None => "ok".to_string(),
+                };
+                let mut log = self.0.lock().unwrap();
+                log.push(format!("{}({args}) {outcome}", invocation.method));
+                Ok(result)
+            }
+        }
+
+        fn invocation() -> Invocation {
+            Invocation {
+                owner: "UserService",
+                method: "delete",
+            }
+        }
+
+        let audit = Audit::default();
+        let app = App::new()
+            .use_config_str("")
+            .add_component(audit.clone())
+            .build()
+            .await
+            .unwrap();
+        App::scope(app, async {
+            let delete = |(user,): (String,)| async move { Ok(user.len() as u32) };
+            let deleted: Result<u32> =
+                intercept::<Audit, _, _, _, _, _>(invocation(), &["user"], ("alice".to_string(),), delete).await;
+            assert_eq!(deleted.unwrap(), 5);
+            let rejected: Result<u32> =
+                intercept::<Audit, _, _, _, _, _>(invocation(), &["user"], ("root".to_string(),), delete).await;
+            assert!(rejected.is_err());
+        })
+        .await;
+        assert_eq!(*audit.0.lock().unwrap(), ["delete([(\"user\", \"alice\")]) ok"]);
+    }
+}
@@ -7,18 +7,39 @@
 /// App Builder
 pub mod application;
 mod autowired;
-/// Banner
+/// Banner printed at startup
 pub mod banner;
+/// Caching of service results
+pub mod cache;
 /// Plugin system: Through the documentation of this module you will learn how to implement your own plugins
 pub mod component;
 /// Config System:
 pub mod config;
 /// spring-rs definition error
 pub mod error;
+/// Managed task executor
+pub mod executor;
+/// Build and application info
+pub mod info;
+/// Method interceptors
+pub mod interceptor;
+/// Circuit breakers and bulkheads
+pub mod resilience;
+/// Retries with backoff
+pub mod retry;
+/// Command line runners and one-off commands
+pub mod runner;
+/// Test support
+pub mod testing;
+/// Startup timeline
+pub mod timeline;
 /// The log plugin is a built-in plugin of spring-rs and is also the first plugin loaded when the application starts.
 pub use application::App;
 pub use async_trait::async_trait;
 pub use springboot_macros::auto_config;
+pub use springboot_macros::command;
+pub use springboot_macros::starter;
+pub use springboot_macros::async_method;
 
 
 
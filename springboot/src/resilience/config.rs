@@ -0,0 +1,73 @@
+use crate::config::Configurable;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// `[resilience.<name>]`, circuit breakers and bulkheads by name
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct ResilienceConfig(pub HashMap<String, ResilienceSpec>);
+
+impl Configurable for ResilienceConfig {
+    fn config_prefix() -> &'static str {
+        "resilience"
+    }
+}
+
+/// Settings of a circuit breaker and its bulkhead
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResilienceSpec {
+    /// Percentage of failed calls in the window that opens the breaker
+    #[serde(default = "default_failure_rate_threshold")]
+    pub failure_rate_threshold: f64,
+    /// Number of recent calls the failure rate is computed over
+    #[serde(default = "default_sliding_window")]
+    pub sliding_window: usize,
+    /// Calls needed in the window before the failure rate is evaluated
+    #[serde(default = "default_minimum_calls")]
+    pub minimum_calls: usize,
+    /// Seconds the breaker stays open before letting probes through
+    #[serde(default = "default_open_duration")]
+    pub open_duration: u64,
+    /// Probes let through while half open, the breaker closes when they all succeed
+    #[serde(default = "default_half_open_calls")]
+    pub half_open_calls: usize,
+    /// Calls running at the same time, the bulkhead is unbounded if absent
+    pub max_concurrent_calls: Option<usize>,
+    /// Milliseconds a call waits for the bulkhead before it is rejected
+    #[serde(default)]
+    pub max_wait: u64,
+}
+
+impl Default for ResilienceSpec {
+    fn default() -> Self {
+        Self {
+            failure_rate_threshold: default_failure_rate_threshold(),
+            sliding_window: default_sliding_window(),
+            minimum_calls: default_minimum_calls(),
+            open_duration: default_open_duration(),
+            half_open_calls: default_half_open_calls(),
+            max_concurrent_calls: None,
+            max_wait: 0,
+        }
+    }
+}
+
+fn default_failure_rate_threshold() -> f64 {
+    50.0
+}
+
+fn default_sliding_window() -> usize {
+    20
+}
+
+fn default_minimum_calls() -> usize {
+    10
+}
+
+fn default_open_duration() -> u64 {
+    30
+}
+
+fn default_half_open_calls() -> usize {
+    3
+}
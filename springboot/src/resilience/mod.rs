@@ -0,0 +1,541 @@
+//! Circuit breakers and bulkheads protecting calls to unreliable dependencies.
+//!
+//! They are looked up by name on the [Resilience] component and configured under `[resilience.<name>]`:
+//!
+//! ```toml
+//! [resilience.mongo]
+//! failure_rate_threshold = 50
+//! sliding_window = 20
+//! minimum_calls = 10
+//! open_duration = 30
+//! half_open_calls = 3
+//! max_concurrent_calls = 16
+//! max_wait = 100
+//! ```
+pub mod config;
+
+use crate::application::{App, AppBuilder};
+use crate::component::{ComponentRegistry, MutableComponentRegistry};
+use crate::config::ConfigRegistry;
+use crate::error::{AppError, Result};
+use config::{ResilienceConfig, ResilienceSpec};
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+pub use springboot_macros::circuit_breaker;
+
+/// State of a [CircuitBreaker]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Calls go through, their outcomes are recorded
+    Closed,
+    /// Calls are rejected until the open duration elapsed
+    Open,
+    /// A few probes go through to find out if the dependency recovered
+    HalfOpen,
+}
+
+impl Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        };
+        f.pad(state)
+    }
+}
+
+/// Circuit breaker opening when the failure rate of the recent calls exceeds the threshold
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    name: Arc<str>,
+    spec: Arc<ResilienceSpec>,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+struct BreakerState {
+    state: CircuitState,
+    /// outcomes of the recent calls, true if failed
+    window: VecDeque<bool>,
+    opened_at: Instant,
+    probes: usize,
+    probe_successes: usize,
+    not_permitted: u64,
+}
+
+impl BreakerState {
+    /// Move to half open once the open duration elapsed
+    fn refresh(&mut self, open_duration: Duration) {
+        if self.state == CircuitState::Open && self.opened_at.elapsed() >= open_duration {
+            self.state = CircuitState::HalfOpen;
+            self.probes = 0;
+            self.probe_successes = 0;
+        }
+    }
+
+    fn failure_rate(&self) -> f64 {
+        match self.window.len() {
+            0 => 0.0,
+            len => self.window.iter().filter(|failed| **failed).count() as f64 * 100.0 / len as f64,
+        }
+    }
+}
+
+/// Permission to make a call through a [CircuitBreaker], the outcome must be reported.
+/// A permit dropped without an outcome is not recorded.
+pub struct CircuitPermit {
+    breaker: CircuitBreaker,
+    probe: bool,
+    recorded: bool,
+}
+
+impl CircuitPermit {
+    /// Record a successful call
+    pub fn success(mut self) {
+        self.recorded = true;
+        self.breaker.record(self.probe, false);
+    }
+
+    /// Record a failed call
+    pub fn failure(mut self) {
+        self.recorded = true;
+        self.breaker.record(self.probe, true);
+    }
+}
+
+impl Drop for CircuitPermit {
+    fn drop(&mut self) {
+        if self.probe && !self.recorded {
+            let mut state = self.breaker.state.lock().unwrap();
+            if state.state == CircuitState::HalfOpen {
+                state.probes -= 1;
+            }
+        }
+    }
+}
+
+/// Snapshot of a [CircuitBreaker], for health checks and metrics
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitBreakerMetrics {
+    /// Name of the breaker
+    pub name: String,
+    /// Current state
+    pub state: CircuitState,
+    /// Percentage of failed calls in the window
+    pub failure_rate: f64,
+    /// Calls in the window
+    pub buffered_calls: usize,
+    /// Failed calls in the window
+    pub failed_calls: usize,
+    /// Calls rejected since the start
+    pub not_permitted_calls: u64,
+}
+
+impl CircuitBreaker {
+    /// Create a breaker with the given settings
+    pub fn new(name: impl Into<String>, spec: ResilienceSpec) -> Self {
+        Self {
+            name: name.into().into(),
+            spec: Arc::new(spec),
+            state: Arc::new(Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                window: VecDeque::new(),
+                opened_at: Instant::now(),
+                probes: 0,
+                probe_successes: 0,
+                not_permitted: 0,
+            })),
+        }
+    }
+
+    /// Name of the breaker
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn open_duration(&self) -> Duration {
+        Duration::from_secs(self.spec.open_duration)
+    }
+
+    /// Current state
+    pub fn state(&self) -> CircuitState {
+        let mut state = self.state.lock().unwrap();
+        state.refresh(self.open_duration());
+        state.state
+    }
+
+    /// Ask for permission to make a call, fails with [AppError::CallNotPermitted] if the breaker is open
+    pub fn try_acquire(&self) -> Result<CircuitPermit> {
+        let mut state = self.state.lock().unwrap();
+        state.refresh(self.open_duration());
+        let probe = match state.state {
+            CircuitState::Closed => false,
+            CircuitState::HalfOpen if state.probes < self.spec.half_open_calls.max(1) => {
+                state.probes += 1;
+                true
+            }
+            CircuitState::HalfOpen | CircuitState::Open => {
+                state.not_permitted += 1;
+                return Err(AppError::CallNotPermitted(self.name.to_string()));
+            }
+        };
+        Ok(CircuitPermit {
+            breaker: self.clone(),
+            probe,
+            recorded: false,
+        })
+    }
+
+    fn record(&self, probe: bool, failed: bool) {
+        let mut state = self.state.lock().unwrap();
+        match state.state {
+            CircuitState::Closed if !probe => {
+                state.window.push_back(failed);
+                while state.window.len() > self.spec.sliding_window.max(1) {
+                    state.window.pop_front();
+                }
+                if state.window.len() >= self.spec.minimum_calls
+                    && state.failure_rate() >= self.spec.failure_rate_threshold
+                {
+                    log::warn!(
+                        "circuit breaker {} opened, failure rate {:.1}%",
+                        self.name,
+                        state.failure_rate()
+                    );
+                    self.open(&mut state);
+                }
+            }
+            CircuitState::HalfOpen if probe => {
+                if failed {
+                    log::warn!("circuit breaker {} probe failed, opened again", self.name);
+                    self.open(&mut state);
+                } else {
+                    state.probe_successes += 1;
+                    if state.probe_successes >= self.spec.half_open_calls.max(1) {
+                        log::info!("circuit breaker {} closed", self.name);
+                        state.state = CircuitState::Closed;
+                        state.window.clear();
+                    }
+                }
+            }
+            // the outcome of a call started in an earlier state
+            _ => {}
+        }
+    }
+
+    fn open(&self, state: &mut BreakerState) {
+        state.state = CircuitState::Open;
+        state.opened_at = Instant::now();
+        state.window.clear();
+    }
+
+    /// Run the call if the breaker permits it and record its outcome
+    pub async fn call<T, E, F>(&self, call: F) -> std::result::Result<T, E>
+    where
+        F: Future<Output = std::result::Result<T, E>>,
+        E: From<AppError>,
+    {
+        let permit = self.try_acquire()?;
+        let result = call.await;
+        match &result {
+            Ok(_) => permit.success(),
+            Err(_) => permit.failure(),
+        }
+        result
+    }
+
+    /// Snapshot of the state and the recorded calls
+    pub fn metrics(&self) -> CircuitBreakerMetrics {
+        let mut state = self.state.lock().unwrap();
+        state.refresh(self.open_duration());
+        CircuitBreakerMetrics {
+            name: self.name.to_string(),
+            state: state.state,
+            failure_rate: state.failure_rate(),
+            buffered_calls: state.window.len(),
+            failed_calls: state.window.iter().filter(|failed| **failed).count(),
+            not_permitted_calls: state.not_permitted,
+        }
+    }
+}
+
+/// Caps the calls running at the same time
+#[derive(Clone)]
+pub struct Bulkhead {
+    name: Arc<str>,
+    max_concurrent_calls: Option<usize>,
+    max_wait: Duration,
+    semaphore: Option<Arc<Semaphore>>,
+    rejected: Arc<AtomicU64>,
+}
+
+/// A slot of a [Bulkhead], released when dropped
+pub struct BulkheadPermit(#[allow(dead_code)] Option<OwnedSemaphorePermit>);
+
+/// Snapshot of a [Bulkhead], for health checks and metrics
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkheadMetrics {
+    /// Name of the bulkhead
+    pub name: String,
+    /// Configured cap, absent if unbounded
+    pub max_concurrent_calls: Option<usize>,
+    /// Free slots, absent if unbounded
+    pub available: Option<usize>,
+    /// Calls rejected since the start
+    pub rejected_calls: u64,
+}
+
+impl Bulkhead {
+    /// Create a bulkhead, it is unbounded if the spec has no `max_concurrent_calls`
+    pub fn new(name: impl Into<String>, spec: &ResilienceSpec) -> Self {
+        Self {
+            name: name.into().into(),
+            max_concurrent_calls: spec.max_concurrent_calls,
+            max_wait: Duration::from_millis(spec.max_wait),
+            semaphore: spec.max_concurrent_calls.map(|max| Arc::new(Semaphore::new(max))),
+            rejected: Default::default(),
+        }
+    }
+
+    /// Name of the bulkhead
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Wait up to `max_wait` for a slot, fails with [AppError::BulkheadFull]
+    pub async fn acquire(&self) -> Result<BulkheadPermit> {
+        let Some(semaphore) = &self.semaphore else {
+            return Ok(BulkheadPermit(None));
+        };
+        let permit = if self.max_wait.is_zero() {
+            semaphore.clone().try_acquire_owned().ok()
+        } else {
+            tokio::time::timeout(self.max_wait, semaphore.clone().acquire_owned())
+                .await
+                .ok()
+                .and_then(|permit| permit.ok())
+        };
+        match permit {
+            Some(permit) => Ok(BulkheadPermit(Some(permit))),
+            None => {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                Err(AppError::BulkheadFull(self.name.to_string()))
+            }
+        }
+    }
+
+    /// Run the call once a slot is free
+    pub async fn call<T, E, F>(&self, call: F) -> std::result::Result<T, E>
+    where
+        F: Future<Output = std::result::Result<T, E>>,
+        E: From<AppError>,
+    {
+        let _permit = self.acquire().await?;
+        call.await
+    }
+
+    /// Snapshot of the slots
+    pub fn metrics(&self) -> BulkheadMetrics {
+        BulkheadMetrics {
+            name: self.name.to_string(),
+            max_concurrent_calls: self.max_concurrent_calls,
+            available: self.semaphore.as_ref().map(|s| s.available_permits()),
+            rejected_calls: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Snapshot of all circuit breakers and bulkheads
+#[derive(Debug, Clone, Serialize)]
+pub struct ResilienceMetrics {
+    /// Circuit breakers by name
+    pub circuit_breakers: Vec<CircuitBreakerMetrics>,
+    /// Bulkheads by name
+    pub bulkheads: Vec<BulkheadMetrics>,
+}
+
+impl ResilienceMetrics {
+    /// False if a circuit breaker is open
+    pub fn is_healthy(&self) -> bool {
+        self.circuit_breakers
+            .iter()
+            .all(|breaker| breaker.state != CircuitState::Open)
+    }
+}
+
+/// Component holding the circuit breakers and bulkheads by name
+#[derive(Clone)]
+pub struct Resilience {
+    config: Arc<ResilienceConfig>,
+    circuit_breakers: Arc<DashMap<String, CircuitBreaker>>,
+    bulkheads: Arc<DashMap<String, Bulkhead>>,
+}
+
+impl Resilience {
+    /// Create the breakers and bulkheads of the config,
+    /// others are created with the default settings when first asked for
+    pub fn new(config: ResilienceConfig) -> Self {
+        let resilience = Self {
+            config: Arc::new(config),
+            circuit_breakers: Default::default(),
+            bulkheads: Default::default(),
+        };
+        for name in resilience.config.0.keys() {
+            resilience.circuit_breaker(name);
+            resilience.bulkhead(name);
+        }
+        resilience
+    }
+
+    /// The resilience component of the [current app](App::current), if there is one
+    pub fn current() -> Option<Self> {
+        App::try_current().ok()?.get_component::<Self>()
+    }
+
+    fn spec(&self, name: &str) -> ResilienceSpec {
+        self.config.0.get(name).cloned().unwrap_or_default()
+    }
+
+    /// The circuit breaker with the given name
+    pub fn circuit_breaker(&self, name: &str) -> CircuitBreaker {
+        self.circuit_breakers
+            .entry(name.to_string())
+            .or_insert_with(|| CircuitBreaker::new(name, self.spec(name)))
+            .clone()
+    }
+
+    /// The bulkhead with the given name
+    pub fn bulkhead(&self, name: &str) -> Bulkhead {
+        self.bulkheads
+            .entry(name.to_string())
+            .or_insert_with(|| Bulkhead::new(name, &self.spec(name)))
+            .clone()
+    }
+
+    /// Snapshot of all circuit breakers and bulkheads, sorted by name
+    pub fn metrics(&self) -> ResilienceMetrics {
+        let mut circuit_breakers: Vec<_> = self.circuit_breakers.iter().map(|b| b.metrics()).collect();
+        circuit_breakers.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut bulkheads: Vec<_> = self.bulkheads.iter().map(|b| b.metrics()).collect();
+        bulkheads.sort_by(|a, b| a.name.cmp(&b.name));
+        ResilienceMetrics {
+            circuit_breakers,
+            bulkheads,
+        }
+    }
+}
+
+/// Code generated by `#[circuit_breaker]`: run the call through the breaker and the bulkhead with the given name.
+/// A call rejected by the bulkhead is not recorded by the breaker,
+/// nor is an error for which `record_on` returns `false`.
+#[doc(hidden)]
+pub async fn guarded<T, E, F>(name: &str, record_on: fn(&E) -> bool, call: F) -> std::result::Result<T, E>
+where
+    F: Future<Output = std::result::Result<T, E>>,
+    E: From<AppError>,
+{
+    let Some(resilience) = Resilience::current() else {
+        return call.await;
+    };
+    let permit = resilience.circuit_breaker(name).try_acquire()?;
+    let _slot = resilience.bulkhead(name).acquire().await?;
+    let result = call.await;
+    match &result {
+        Ok(_) => permit.success(),
+        Err(e) if record_on(e) => permit.failure(),
+        // e.g. an invalid argument says nothing about the health of the callee
+        Err(_) => drop(permit),
+    }
+    result
+}
+
+/// Register the [Resilience] component built from `[resilience]`
+pub(crate) fn add_resilience_component(app: &mut AppBuilder) -> Result<()> {
+    if app.has_component::<Resilience>() {
+        return Ok(());
+    }
+    let config = app.get_config::<ResilienceConfig>()?;
+    app.add_component(Resilience::new(config));
+    Ok(())
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::config::{ResilienceConfig, ResilienceSpec};
+    use super::{Bulkhead, CircuitBreaker, CircuitState, Resilience};
+    use crate::error::AppError;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        let spec = ResilienceSpec {
+            failure_rate_threshold: 50.0,
+            sliding_window: 4,
+            minimum_calls: 4,
+            open_duration: 0,
+            half_open_calls: 2,
+            ..Default::default()
+        };
+        let breaker = CircuitBreaker::new("mongo", ResilienceSpec { open_duration: 60, ..spec.clone() });
+        for failed in [false, true, false, true] {
+            let permit = breaker.try_acquire().unwrap();
+            if failed {
+                permit.failure()
+            } else {
+                permit.success()
+            }
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(matches!(breaker.try_acquire(), Err(AppError::CallNotPermitted(name)) if name == "mongo"));
+        let result: Result<(), AppError> = breaker.call(async { Ok(()) }).await;
+        assert!(result.is_err());
+        assert_eq!(breaker.metrics().not_permitted_calls, 2);
+
+        let breaker = CircuitBreaker::new("mongo", spec);
+        for _ in 0..4 {
+            breaker.try_acquire().unwrap().failure();
+        }
+        // the open duration is zero, so probes are let through right away
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        let first = breaker.try_acquire().unwrap();
+        let second = breaker.try_acquire().unwrap();
+        assert!(breaker.try_acquire().is_err());
+        drop(second);
+        breaker.try_acquire().unwrap().success();
+        first.success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_bulkhead() {
+        let spec = ResilienceSpec {
+            max_concurrent_calls: Some(1),
+            ..Default::default()
+        };
+        let mut config = ResilienceConfig::default();
+        config.0.insert("mail".to_string(), spec);
+        let resilience = Resilience::new(config);
+        let bulkhead = resilience.bulkhead("mail");
+
+        let permit = bulkhead.acquire().await.unwrap();
+        assert!(matches!(bulkhead.acquire().await, Err(AppError::BulkheadFull(_))));
+        drop(permit);
+        assert!(bulkhead.acquire().await.is_ok());
+        assert!(resilience.bulkhead("other").acquire().await.is_ok());
+
+        let metrics = resilience.metrics();
+        assert!(metrics.is_healthy());
+        assert_eq!(metrics.bulkheads[0].name, "mail");
+        assert_eq!(metrics.bulkheads[0].rejected_calls, 1);
+        assert_eq!(metrics.circuit_breakers[0].state, CircuitState::Closed);
+    }
+}
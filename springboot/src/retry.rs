@@ -0,0 +1,152 @@
+//! Retries of fallible async calls with exponential backoff, used by `#[retryable]`.
+use std::collections::hash_map::RandomState;
+use std::fmt::Debug;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+pub use springboot_macros::{recover, retryable};
+
+/// How often and how long to wait between the attempts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// Attempts including the first call, at least one attempt is made
+    pub max_attempts: u32,
+    /// Delay before the second attempt
+    pub delay: Duration,
+    /// Factor the delay grows by after each attempt
+    pub multiplier: f64,
+    /// Upper bound of the delay
+    pub max_delay: Duration,
+    /// Wait a random time between half and the full delay, so callers failing together don't retry together
+    pub jitter: bool,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            delay: Duration::from_millis(100),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay after the given failed attempt, starting at 1
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(attempt.saturating_sub(1) as i32);
+        let delay = self.delay.mul_f64(factor).min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+        let random = RandomState::new().build_hasher().finish();
+        let half = delay / 2;
+        half + half.mul_f64((random % 1000) as f64 / 1000.0)
+    }
+}
+
+/// Call `f` until it succeeds, `retry_on` returns false for its error, or the attempts are exhausted.
+/// The last error is returned.
+pub async fn retry<T, E, F, Fut>(name: &str, backoff: &Backoff, retry_on: fn(&E) -> bool, mut f: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Debug,
+{
+    let mut attempt = 1;
+    loop {
+        match f().await {
+            Ok(value) => {
+                if attempt > 1 {
+                    log::info!("{name} succeeded on attempt {attempt}/{}", backoff.max_attempts);
+                }
+                return Ok(value);
+            }
+            Err(e) if attempt < backoff.max_attempts && retry_on(&e) => {
+                let delay = backoff.delay(attempt);
+                log::warn!(
+                    "{name} failed on attempt {attempt}/{}, retrying in {delay:?}: {e:?}",
+                    backoff.max_attempts
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                if attempt > 1 {
+                    log::error!("{name} failed after {attempt} attempts: {e:?}");
+                }
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// Retry on every error
+pub fn any_error<E>(_: &E) -> bool {
+    true
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{any_error, retry, Backoff};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_backoff_delay() {
+        let backoff = Backoff {
+            max_attempts: 5,
+            delay: Duration::from_millis(100),
+            multiplier: 2.0,
+            max_delay: Duration::from_millis(300),
+            jitter: false,
+        };
+        assert_eq!(backoff.delay(1), Duration::from_millis(100));
+        assert_eq!(backoff.delay(2), Duration::from_millis(200));
+        assert_eq!(backoff.delay(3), Duration::from_millis(300));
+
+        let jitter = Backoff { jitter: true, ..backoff };
+        for attempt in 1..5 {
+            let delay = jitter.delay(attempt);
+            assert!(delay >= backoff.delay(attempt) / 2 && delay <= backoff.delay(attempt));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let backoff = Backoff {
+            delay: Duration::from_millis(1),
+            ..Default::default()
+        };
+        let calls = AtomicU32::new(0);
+        let result = retry("flaky", &backoff, any_error, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err("down"),
+                n => Ok(n),
+            }
+        })
+        .await;
+        assert_eq!(result, Ok(1));
+
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), &str> = retry("broken", &backoff, any_error, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err("down")
+        })
+        .await;
+        assert_eq!(result, Err("down"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), &str> = retry("fatal", &backoff, |e| *e != "fatal", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err("fatal")
+        })
+        .await;
+        assert_eq!(result, Err("fatal"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,412 @@
+//! Runners are executed once the application is ready,
+//! and commands turn the same binary into a one-off maintenance tool.
+use crate::application::App;
+use crate::component::component::ComponentRef;
+use crate::component::ComponentRegistry;
+use crate::config::{ConfigRef, ConfigRegistry, Configurable};
+use crate::error::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub use inventory::submit;
+pub use springboot_macros::command;
+pub use springboot_macros::runner;
+
+/// Parsed command line arguments of the running application.
+///
+/// Arguments in the form `--name=value` or `--flag` are option arguments,
+/// all other arguments are non-option arguments.
+/// It is also registered as a component, so services can inject it with `#[inject(component)]`.
+#[derive(Debug, Clone, Default)]
+pub struct ApplicationArguments {
+    source_args: Vec<String>,
+    option_args: HashMap<String, Vec<String>>,
+    non_option_args: Vec<String>,
+}
+
+impl ApplicationArguments {
+    /// Parse the given arguments, the program name must not be included.
+    pub fn new<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let source_args: Vec<String> = args.into_iter().map(Into::into).collect();
+        let mut option_args: HashMap<String, Vec<String>> = HashMap::new();
+        let mut non_option_args = vec![];
+        for arg in &source_args {
+            match arg.strip_prefix("--") {
+                Some(option) if !option.is_empty() => match option.split_once('=') {
+                    Some((name, value)) => option_args
+                        .entry(name.to_string())
+                        .or_default()
+                        .push(value.to_string()),
+                    None => {
+                        option_args.entry(option.to_string()).or_default();
+                    }
+                },
+                _ => non_option_args.push(arg.clone()),
+            }
+        }
+        Self {
+            source_args,
+            option_args,
+            non_option_args,
+        }
+    }
+
+    /// Parse the arguments of the current process
+    pub fn from_env() -> Self {
+        Self::new(std::env::args().skip(1))
+    }
+
+    /// The raw unprocessed arguments
+    pub fn source_args(&self) -> &[String] {
+        &self.source_args
+    }
+
+    /// Names of all option arguments
+    pub fn option_names(&self) -> impl Iterator<Item = &str> {
+        self.option_args.keys().map(String::as_str)
+    }
+
+    /// Whether the option argument with the given name was present
+    pub fn contains_option(&self, name: &str) -> bool {
+        self.option_args.contains_key(name)
+    }
+
+    /// Values of the option argument with the given name.
+    /// `--flag` yields an empty slice, a missing option yields `None`.
+    pub fn get_option_values(&self, name: &str) -> Option<&[String]> {
+        self.option_args.get(name).map(Vec::as_slice)
+    }
+
+    /// The non-option arguments
+    pub fn non_option_args(&self) -> &[String] {
+        &self.non_option_args
+    }
+}
+
+/// Runner that receives the raw command line arguments once the application is ready.
+#[async_trait]
+pub trait CommandLineRunner: Send + Sync + 'static {
+    /// Run the runner
+    async fn run(&self, app: Arc<App>, args: &[String]) -> Result<()>;
+
+    /// Runners with a lower order are executed first
+    fn order(&self) -> i32 {
+        0
+    }
+}
+
+/// Runner that receives the parsed [ApplicationArguments] once the application is ready.
+#[async_trait]
+pub trait ApplicationRunner: Send + Sync + 'static {
+    /// Run the runner
+    async fn run(&self, app: Arc<App>, args: &ApplicationArguments) -> Result<()>;
+
+    /// Runners with a lower order are executed first
+    fn order(&self) -> i32 {
+        0
+    }
+}
+
+/// A runner added with [`AppBuilder`](crate::application::AppBuilder) or found by [RunnerRegistrar]
+#[derive(Clone)]
+pub enum RunnerRef {
+    /// A [CommandLineRunner]
+    CommandLine(Arc<dyn CommandLineRunner>),
+    /// An [ApplicationRunner]
+    Application(Arc<dyn ApplicationRunner>),
+}
+
+impl RunnerRef {
+    fn order(&self) -> i32 {
+        match self {
+            Self::CommandLine(runner) => runner.order(),
+            Self::Application(runner) => runner.order(),
+        }
+    }
+
+    async fn run(&self, app: Arc<App>, args: &ApplicationArguments) -> Result<()> {
+        match self {
+            Self::CommandLine(runner) => runner.run(app, args.source_args()).await,
+            Self::Application(runner) => runner.run(app, args).await,
+        }
+    }
+}
+
+/// Runner component registered by the `#[runner]` macro
+pub trait RunnerRegistrar: Send + Sync + 'static {
+    /// Type name of the runner component
+    fn name(&self) -> &'static str;
+
+    /// The runner component of the app, `None` if the app doesn't have it
+    fn resolve(&self, app: &App) -> Option<RunnerRef>;
+}
+
+inventory::collect!(&'static dyn RunnerRegistrar);
+
+/// Register a [RunnerRegistrar], used by the `#[runner]` macro
+#[macro_export]
+macro_rules! submit_runner {
+    ($ty:ident) => {
+        ::springboot::runner::submit! {
+            &$ty as &dyn ::springboot::runner::RunnerRegistrar
+        }
+    };
+}
+
+/// The runner components of the app
+pub(crate) fn discover_runners(app: &App) -> Vec<RunnerRef> {
+    inventory::iter::<&dyn RunnerRegistrar>
+        .into_iter()
+        .filter_map(|registrar| {
+            let runner = registrar.resolve(app);
+            if runner.is_none() {
+                log::debug!("runner {} is not a component of the app", registrar.name());
+            }
+            runner
+        })
+        .collect()
+}
+
+/// Execute the runners in order, the first failure aborts the remaining runners
+pub(crate) async fn call_runners(
+    mut runners: Vec<RunnerRef>,
+    app: Arc<App>,
+    args: &ApplicationArguments,
+) -> Result<()> {
+    runners.sort_by_key(RunnerRef::order);
+    for runner in runners {
+        runner.run(app.clone(), args).await?;
+    }
+    Ok(())
+}
+
+//////////////////////////////////////////////////
+/// Values that can be resolved from the built [App] as arguments of a `#[command]` function
+pub trait FromApp: Sized {
+    /// Resolve the value from the app
+    fn from_app(app: &Arc<App>, args: &ApplicationArguments) -> Result<Self>;
+}
+
+impl FromApp for Arc<App> {
+    fn from_app(app: &Arc<App>, _args: &ApplicationArguments) -> Result<Self> {
+        Ok(app.clone())
+    }
+}
+
+impl FromApp for ApplicationArguments {
+    fn from_app(_app: &Arc<App>, args: &ApplicationArguments) -> Result<Self> {
+        Ok(args.clone())
+    }
+}
+
+impl<T> FromApp for ComponentRef<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn from_app(app: &Arc<App>, _args: &ApplicationArguments) -> Result<Self> {
+        app.try_get_component_ref::<T>()
+    }
+}
+
+impl<T> FromApp for ConfigRef<T>
+where
+    T: serde::de::DeserializeOwned + Configurable,
+{
+    fn from_app(app: &Arc<App>, _args: &ApplicationArguments) -> Result<Self> {
+        Ok(ConfigRef::new(app.get_config::<T>()?))
+    }
+}
+
+/// The exit status of a `#[command]` function
+pub trait ExitStatus {
+    /// Process exit code, `0` means success
+    fn exit_code(self) -> i32;
+}
+
+impl ExitStatus for () {
+    fn exit_code(self) -> i32 {
+        0
+    }
+}
+
+impl ExitStatus for i32 {
+    fn exit_code(self) -> i32 {
+        self
+    }
+}
+
+impl<T, E> ExitStatus for std::result::Result<T, E>
+where
+    T: ExitStatus,
+    E: std::fmt::Debug,
+{
+    fn exit_code(self) -> i32 {
+        match self {
+            Ok(status) => status.exit_code(),
+            Err(e) => {
+                log::error!("command failed: {:?}", e);
+                1
+            }
+        }
+    }
+}
+
+/// One-off command registered by the `#[command]` macro
+#[async_trait]
+pub trait CommandRegistrar: Send + Sync + 'static {
+    /// Name used to select the command, as the first non-option argument or with `--command=<name>`
+    fn name(&self) -> &'static str;
+
+    /// Run the command and return the process exit code
+    async fn run(&self, app: Arc<App>, args: ApplicationArguments) -> Result<i32>;
+}
+
+inventory::collect!(&'static dyn CommandRegistrar);
+
+/// Register a [CommandRegistrar], used by the `#[command]` macro
+#[macro_export]
+macro_rules! submit_command {
+    ($ty:ident) => {
+        ::springboot::runner::submit! {
+            &$ty as &dyn ::springboot::runner::CommandRegistrar
+        }
+    };
+}
+
+/// Find the command selected by the first non-option argument, `./app reindex`,
+/// or by the `--command=<name>` option.
+///
+/// A name matching no command is an error listing the known ones. The non-option arguments
+/// are left to the runners of a binary without commands.
+pub(crate) fn find_command(args: &ApplicationArguments) -> Result<Option<&'static dyn CommandRegistrar>> {
+    select_command(args, inventory::iter::<&dyn CommandRegistrar>.into_iter().copied())
+}
+
+fn select_command<'a>(
+    args: &ApplicationArguments,
+    commands: impl IntoIterator<Item = &'a dyn CommandRegistrar>,
+) -> Result<Option<&'a dyn CommandRegistrar>> {
+    let commands: Vec<_> = commands.into_iter().collect();
+    let name = match args.get_option_values("command") {
+        Some(values) => values.first(),
+        None if commands.is_empty() => None,
+        None => args.non_option_args().first(),
+    };
+    let Some(name) = name else {
+        return Ok(None);
+    };
+    match commands.iter().find(|command| command.name() == name) {
+        Some(command) => Ok(Some(*command)),
+        None => {
+            let mut known: Vec<&str> = commands.iter().map(|command| command.name()).collect();
+            known.sort();
+            Err(anyhow::anyhow!("unknown command `{name}`, the known commands are: {}", known.join(", ")).into())
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{select_command, ApplicationArguments, ApplicationRunner, CommandLineRunner, CommandRegistrar};
+    use crate::error::Result;
+    use crate::App;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_parse_arguments() {
+        let args = ApplicationArguments::new(["reindex", "--all", "--batch=10", "--batch=20", "posts"]);
+
+        assert_eq!(args.source_args().len(), 5);
+        assert_eq!(args.non_option_args(), ["reindex", "posts"]);
+        assert!(args.contains_option("all"));
+        assert_eq!(args.get_option_values("all"), Some(&[][..]));
+        assert_eq!(
+            args.get_option_values("batch"),
+            Some(&["10".to_string(), "20".to_string()][..])
+        );
+        assert!(args.get_option_values("missing").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_runners_order() {
+        #[derive(Clone, Default)]
+        struct Calls(Arc<Mutex<Vec<String>>>);
+
+        struct First(Calls);
+        struct Second(Calls);
+
+        #[async_trait]
+        impl ApplicationRunner for First {
+            async fn run(&self, _app: Arc<App>, args: &ApplicationArguments) -> Result<()> {
+                let value = args.get_option_values("name").unwrap()[0].clone();
+                self.0 .0.lock().unwrap().push(format!("first:{value}"));
+                Ok(())
+            }
+
+            fn order(&self) -> i32 {
+                -1
+            }
+        }
+
+        #[async_trait]
+        impl CommandLineRunner for Second {
+            async fn run(&self, _app: Arc<App>, args: &[String]) -> Result<()> {
+                self.0 .0.lock().unwrap().push(format!("second:{}", args.join(" ")));
+                Ok(())
+            }
+        }
+
+        let calls = Calls::default();
+        App::new()
+            .use_args(ApplicationArguments::new(["--name=spring"]))
+            .add_command_line_runner(Second(calls.clone()))
+            .add_application_runner(First(calls.clone()))
+            .run()
+            .await;
+
+        assert_eq!(
+            *calls.0.lock().unwrap(),
+            vec!["first:spring".to_string(), "second:--name=spring".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_select_command() {
+        struct Named(&'static str);
+
+        #[async_trait]
+        impl CommandRegistrar for Named {
+            fn name(&self) -> &'static str {
+                self.0
+            }
+
+            async fn run(&self, _app: Arc<App>, _args: ApplicationArguments) -> Result<i32> {
+                Ok(0)
+            }
+        }
+
+        let commands: [&dyn CommandRegistrar; 2] = [&Named("reindex"), &Named("migrate")];
+        let select = |args: &[&str]| {
+            select_command(&ApplicationArguments::new(args.iter().copied()), commands)
+                .map(|command| command.map(|command| command.name()))
+                .map_err(|e| e.to_string())
+        };
+
+        assert_eq!(select(&["reindex", "--batch=10"]), Ok(Some("reindex")));
+        assert_eq!(select(&["--command=migrate"]), Ok(Some("migrate")));
+        assert_eq!(select(&["--batch=10"]), Ok(None));
+        assert_eq!(
+            select(&["reindx"]),
+            Err("unknown command `reindx`, the known commands are: migrate, reindex".to_string())
+        );
+        // a binary without commands leaves the non-option arguments to its runners
+        let none = select_command(&ApplicationArguments::new(["posts"]), []).map(|command| command.is_none());
+        assert!(none.unwrap());
+    }
+}
@@ -0,0 +1,220 @@
+//! Test support: build an isolated [App] from inline configuration,
+//! replace components with mocks and run the shutdown hooks on teardown.
+//!
+//! ```
+//! use springboot::component::ComponentRegistry;
+//! use springboot::testing::TestApp;
+//!
+//! #[derive(Clone)]
+//! struct MailSender(&'static str);
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> springboot::error::Result<()> {
+//! let app = TestApp::build(
+//!     TestApp::builder(r#"
+//!         [mail]
+//!         host = "localhost"
+//!     "#)
+//!     .override_component(MailSender("mock")),
+//! )
+//! .await?;
+//!
+//! assert_eq!(app.get_expect_component::<MailSender>().0, "mock");
+//! app.shutdown().await?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::application::{App, AppBuilder};
+use crate::config::env::Env;
+use crate::error::Result;
+use crate::runner::ApplicationArguments;
+use std::cell::RefCell;
+use std::{ops::Deref, sync::Arc};
+
+/// A test app scoped to the thread and the app it replaced
+type ScopedTestApp = (Arc<App>, Option<Arc<App>>);
+
+thread_local! {
+    /// The test apps scoped to the current thread, in the order they were built
+    static TEST_APPS: RefCell<Vec<ScopedTestApp>> = const { RefCell::new(Vec::new()) };
+}
+
+/// An application built for a test.
+///
+/// While the `TestApp` is alive, [`App::global`] and [`get_service`](crate::application::get_service)
+/// return this app on the current thread, so tests running in parallel don't see each other's components.
+/// Use `#[tokio::test]` (current thread runtime) so the code under test runs on the test thread.
+pub struct TestApp {
+    app: Arc<App>,
+    shutdown_hooks: Vec<Box<crate::application::Scheduler<String>>>,
+}
+
+impl TestApp {
+    /// Prepare an [AppBuilder] in the [Env::Test] environment, configured by the given toml content.
+    /// No configuration file is read, the command line arguments of the test binary are ignored
+    /// and the app is not published as the process-wide global app.
+    /// The `#[starter]` structs are not discovered, the starters under test are added explicitly.
+    pub fn builder(toml_content: &str) -> AppBuilder {
+        let mut builder = App::new();
+        builder.env = Env::Test;
+        builder
+            .use_config_str(toml_content)
+            .use_args(ApplicationArguments::default())
+            .use_global(false)
+            .use_starter_discovery(false);
+        builder
+    }
+
+    /// Build the app and scope it to the current thread.
+    /// Components replaced by [`AppBuilder::override_component`] are injected instead of the real ones.
+    pub async fn build(builder: &mut AppBuilder) -> Result<Self> {
+        let app = builder.build().await?;
+        let shutdown_hooks = std::mem::take(&mut builder.shutdown_hooks);
+        let previous = App::set_scoped(Some(app.clone()));
+        TEST_APPS.with_borrow_mut(|apps| apps.push((app.clone(), previous)));
+        Ok(Self { app, shutdown_hooks })
+    }
+
+    /// The built app
+    pub fn app(&self) -> Arc<App> {
+        self.app.clone()
+    }
+
+    /// Run the shutdown hooks in reverse order of registration and release the scoped app.
+    /// Dropping a `TestApp` without calling this method skips the shutdown hooks.
+    pub async fn shutdown(mut self) -> Result<()> {
+        while let Some(hook) = self.shutdown_hooks.pop() {
+            let result = App::scope(self.app.clone(), Box::into_pin(hook(self.app.clone()))).await?;
+            log::info!("shutdown result: {result}");
+        }
+        Ok(())
+    }
+}
+
+impl Deref for TestApp {
+    type Target = App;
+
+    fn deref(&self) -> &Self::Target {
+        &self.app
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        TEST_APPS.with_borrow_mut(|apps| {
+            let Some(index) = apps.iter().position(|(app, _)| Arc::ptr_eq(app, &self.app)) else {
+                return;
+            };
+            let (_, previous) = apps.remove(index);
+            match apps.get_mut(index) {
+                // a later test app is still scoped, it restores the app this one replaced
+                Some((_, replaced)) => *replaced = previous,
+                None => {
+                    App::set_scoped(previous);
+                }
+            }
+        });
+    }
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::TestApp;
+    use crate::application::{get_service, try_get_service, AppBuilder};
+    use crate::component::{ComponentRegistry, MutableComponentRegistry, Starter};
+    use crate::config::{ConfigRegistry, Configurable};
+    use crate::error::Result;
+    use crate::App;
+    use serde::Deserialize;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_override_component() -> Result<()> {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Repository(&'static str);
+
+        #[derive(Debug, Deserialize)]
+        struct RepositoryConfig {
+            name: String,
+        }
+
+        impl Configurable for RepositoryConfig {
+            fn config_prefix() -> &'static str {
+                "repository"
+            }
+        }
+
+        struct RepositoryStarter(Arc<AtomicBool>);
+
+        impl Starter for RepositoryStarter {
+            fn build(&self, app: &mut AppBuilder) {
+                app.add_component(Repository("real"));
+                let closed = self.0.clone();
+                app.add_shutdown_hook(move |_| {
+                    Box::new(async move {
+                        closed.store(true, Ordering::SeqCst);
+                        Ok("repository closed".to_string())
+                    })
+                });
+            }
+        }
+
+        let closed = Arc::new(AtomicBool::new(false));
+        let app = TestApp::build(
+            TestApp::builder(
+                r#"
+                [repository]
+                name = "posts"
+                "#,
+            )
+            .add_starter(RepositoryStarter(closed.clone()))
+            .override_component(Repository("mock")),
+        )
+        .await?;
+
+        assert_eq!(app.get_expect_component::<Repository>(), Repository("mock"));
+        assert_eq!(app.get_config::<RepositoryConfig>()?.name, "posts");
+        assert_eq!(
+            App::global().get_expect_component::<Repository>(),
+            Repository("mock")
+        );
+
+        app.shutdown().await?;
+        assert!(closed.load(Ordering::SeqCst));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scoped_global_app() -> Result<()> {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Repository(&'static str);
+
+        let first = TestApp::build(TestApp::builder("").add_component(Repository("first"))).await?;
+        assert_eq!(get_service::<Repository>(), Repository("first"));
+
+        let second = TestApp::build(TestApp::builder("").add_component(Repository("second"))).await?;
+        assert_eq!(get_service::<Repository>(), Repository("second"));
+
+        second.shutdown().await?;
+        assert_eq!(get_service::<Repository>(), Repository("first"));
+        first.shutdown().await
+    }
+
+    #[tokio::test]
+    async fn test_drop_out_of_order() -> Result<()> {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Repository(&'static str);
+
+        let first = TestApp::build(TestApp::builder("").add_component(Repository("first"))).await?;
+        let second = TestApp::build(TestApp::builder("").add_component(Repository("second"))).await?;
+
+        // the first app is dropped before the second one, which stays scoped
+        drop(first);
+        assert_eq!(get_service::<Repository>(), Repository("second"));
+
+        drop(second);
+        assert!(try_get_service::<Repository>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+//! Timing of the startup phases, to find out which starter or service slows the startup down.
+use std::fmt::{self, Display};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Phase of the startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupPhase {
+    /// Loading the toml configuration
+    Config,
+    /// [`Starter::build`](crate::component::Starter::build)
+    Starter,
+    /// [`ServiceRegistrar::install_service`](crate::component::service::ServiceRegistrar::install_service)
+    Service,
+    /// Spawning the schedulers
+    Scheduler,
+}
+
+impl Display for StartupPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phase = match self {
+            Self::Config => "config",
+            Self::Starter => "starter",
+            Self::Service => "service",
+            Self::Scheduler => "scheduler",
+        };
+        f.pad(phase)
+    }
+}
+
+/// A timed step of the startup
+#[derive(Debug, Clone)]
+pub struct StartupStep {
+    /// Phase of the step
+    pub phase: StartupPhase,
+    /// Name of the starter or service, or a description of the step
+    pub name: String,
+    /// Time taken by the step
+    pub duration: Duration,
+}
+
+/// The timed steps of the startup, in execution order.
+///
+/// It is registered as a component, steps recorded after the app is built show up as well.
+#[derive(Debug, Clone)]
+pub struct StartupTimeline {
+    started: Instant,
+    steps: Arc<RwLock<Vec<StartupStep>>>,
+}
+
+impl StartupTimeline {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            steps: Default::default(),
+        }
+    }
+
+    /// Run `f` and record how long it took
+    pub(crate) fn time<T>(&self, phase: StartupPhase, name: impl Into<String>, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.steps.write().unwrap().push(StartupStep {
+            phase,
+            name: name.into(),
+            duration: start.elapsed(),
+        });
+        result
+    }
+
+    /// The recorded steps in execution order
+    pub fn steps(&self) -> Vec<StartupStep> {
+        self.steps.read().unwrap().clone()
+    }
+
+    /// Time elapsed since the app builder was created
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Table of the steps, the slowest first
+    pub fn report(&self) -> String {
+        let mut steps = self.steps();
+        steps.sort_by_key(|step| std::cmp::Reverse(step.duration));
+        let width = steps.iter().map(|s| s.name.len()).max().unwrap_or(0).max(4);
+        let mut report = format!("startup timeline, total {:?}\n", self.elapsed());
+        report.push_str(&format!("{:<9}  {:<width$}  duration\n", "phase", "name"));
+        for step in steps {
+            report.push_str(&format!(
+                "{:<9}  {:<width$}  {:?}\n",
+                step.phase, step.name, step.duration
+            ));
+        }
+        report
+    }
+}
+
+#[allow(unused_imports)]
+mod tests {
+    use super::{StartupPhase, StartupTimeline};
+    use crate::component::ComponentRegistry;
+    use crate::testing::TestApp;
+    use std::time::Duration;
+
+    #[test]
+    fn test_report_sorted() {
+        let timeline = StartupTimeline::new();
+        timeline.time(StartupPhase::Config, "config/app.toml", || {});
+        timeline.time(StartupPhase::Starter, "SlowStarter", || {
+            std::thread::sleep(Duration::from_millis(20))
+        });
+        let value = timeline.time(StartupPhase::Service, "UserService", || 42);
+
+        assert_eq!(value, 42);
+        let steps = timeline.steps();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].phase, StartupPhase::Config);
+
+        let report = timeline.report();
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[0].starts_with("startup timeline, total"));
+        assert!(lines[2].starts_with("starter    SlowStarter"));
+    }
+
+    #[tokio::test]
+    async fn test_config_str_timed() {
+        let app = TestApp::build(&mut TestApp::builder("[spring]")).await.unwrap();
+        let steps = app.get_expect_component::<StartupTimeline>().steps();
+        assert!(steps.iter().any(|step| step.phase == StartupPhase::Config), "{steps:?}");
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    springboot::info::emit_build_info();
+}
@@ -0,0 +1,20 @@
+use mongodb::bson::oid;
+use springboot_macros::exception_handler;
+use springboot_web::axum::response::IntoResponse;
+use springboot_web::error::{KnownWebError, WebError};
+use thiserror::Error;
+
+/// 按 id 查询的文档不存在
+#[derive(Debug, Error)]
+#[error("document {0} not found")]
+pub struct NotFound(pub String);
+
+#[exception_handler(NotFound)]
+fn not_found(e: &NotFound) -> impl IntoResponse {
+    WebError::from(KnownWebError::not_found(e.to_string()))
+}
+
+#[exception_handler(oid::Error)]
+fn invalid_id(e: &oid::Error) -> impl IntoResponse {
+    WebError::from(KnownWebError::bad_request(e.to_string()))
+}
@@ -1 +1,2 @@
-pub(crate) mod resp;
+pub(crate) mod error;
+pub(crate) mod timing;
@@ -0,0 +1,21 @@
+use springboot::component::service::Service;
+use springboot::error::Result;
+use springboot::interceptor::{Interceptor, Invocation, MethodResult, Proceed};
+use springboot_logger::{info, warn};
+use std::time::Instant;
+
+/// 记录 service 方法的耗时和失败
+#[derive(Clone, Service)]
+pub(crate) struct Timing;
+
+impl Interceptor for Timing {
+    async fn around<R: MethodResult>(&self, invocation: &Invocation, proceed: Proceed<'_, R>) -> Result<R> {
+        let start = Instant::now();
+        let result = proceed.proceed().await;
+        match result.error() {
+            Some(e) => warn!("{} failed after {:?}: {e:?}", invocation.method, start.elapsed()),
+            None => info!("{} took {:?}", invocation.method, start.elapsed()),
+        }
+        Ok(result)
+    }
+}
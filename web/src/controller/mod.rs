@@ -1,4 +1,3 @@
-use crate::common::resp::{Resp, Response};
 use springboot_logger::{debug, error, info, warn};
 // use springboot::
 use springboot_web::get;
@@ -7,10 +6,10 @@ mod post_controller;
 
 
 #[get("/")]
-async fn index() -> Resp {
+async fn index() -> &'static str {
     info!("Hello World");
     debug!("Hello World");
     warn!("Hello World");
     error!("Hello World");
-    Response::ok("Hello World")
+    "Hello World"
 }
@@ -1,23 +1,32 @@
-use crate::common::resp::{Resp, Response};
 use crate::model::BlogPost;
-use crate::services;
-use crate::services::blog_service::query;
+use crate::services::blog_service::BlogService;
 use springboot_logger::info;
 use springboot_macros::{get, post};
 use springboot_web::axum::Json;
-use springboot_web::extractor::Path;
+use springboot_web::controller;
+use springboot_web::error::Result;
+use springboot_web::extractor::{Path, Valid};
 
-// 新增博客
-
-#[post("/post")]
-async fn post_upsert(Json(post): Json<BlogPost>) -> Resp {
-    info!("{:?}", post);
-    let p = services::blog_service::update_or_save(post).await?;
-    Response::ok(p)
+#[controller("/post")]
+struct PostController {
+    #[inject(component)]
+    svc: BlogService,
 }
 
-#[get("/post/{id}")]
-async fn get_post(Path(id): Path<String>) -> Resp {
-    let result = query(id).await?;
-    Response::ok(result)
+#[controller]
+impl PostController {
+    /// 新增或更新博客
+    #[post("")]
+    async fn post_upsert(&self, Valid(Json(post)): Valid<Json<BlogPost>>) -> Result<Json<BlogPost>> {
+        info!("{:?}", post);
+        let p = self.svc.update_or_save(post).await?;
+        Ok(Json(p))
+    }
+
+    /// 按 id 查询博客
+    #[get("/{id}")]
+    async fn get_post(&self, Path(id): Path<String>) -> Result<Json<BlogPost>> {
+        let result = self.svc.query(id).await?;
+        Ok(Json(result))
+    }
 }
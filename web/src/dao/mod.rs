@@ -1,15 +1,16 @@
+use crate::common::error::NotFound;
 use mongodb::bson::oid::ObjectId;
-use mongodb::bson::{doc, Bson};
+use mongodb::bson::{doc, Bson, Document};
 use mongodb::{bson, Client, Collection, Database};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use springboot::application::get_service;
 use springboot::component::service::Service;
 use springboot::config::Configurable;
+use springboot::resilience::circuit_breaker;
+use springboot::retry::retryable;
 use springboot_logger::{debug, info};
 use std::fmt::Debug;
 use std::ops::Deref;
-use std::sync::LazyLock;
 
 pub(crate) mod post_mapper;
 
@@ -37,6 +38,7 @@ pub(crate) struct MongoService {
     #[inject(func = Self::init_db(&config))]
     pub(crate) db: Database,
 
+    #[allow(dead_code)]
     #[inject(config)]
     config: MongoConfig,
 }
@@ -48,20 +50,21 @@ impl MongoService {
     }
 }
 
-static MONGO: LazyLock<MongoService> = LazyLock::new(get_service::<MongoService>);
-
-pub fn get_collection<T>() -> DataBase<Collection<T>>
-where
-    T: Send + Sync,
-{
-    let name = std::any::type_name::<T>();
-    // 按照::分割，取最后一个
-    let name = name.split("::").last().unwrap();
-    // name转换为小写
-    let collection_name = name.to_lowercase();
-    let collection = MONGO.db.collection::<T>(collection_name.as_str());
-    info!("init collection: {}", collection_name);
-    DataBase(collection)
+impl MongoService {
+    /// Collection named after the type
+    pub(crate) fn collection<T>(&self) -> DataBase<Collection<T>>
+    where
+        T: Send + Sync,
+    {
+        let name = std::any::type_name::<T>();
+        // 按照::分割，取最后一个
+        let name = name.split("::").last().unwrap();
+        // name转换为小写
+        let collection_name = name.to_lowercase();
+        let collection = self.db.collection::<T>(collection_name.as_str());
+        debug!("get collection: {}", collection_name);
+        DataBase(collection)
+    }
 }
 
 pub struct DataBase<T>(pub T);
@@ -73,7 +76,28 @@ impl<T> Deref for DataBase<T> {
     }
 }
 
+/// Errors worth retrying, raised while the replica set fails over
+fn is_transient(e: &anyhow::Error) -> bool {
+    use mongodb::error::{ErrorKind, RETRYABLE_WRITE_ERROR, TRANSIENT_TRANSACTION_ERROR};
+    let Some(e) = e.downcast_ref::<mongodb::error::Error>() else {
+        return false;
+    };
+    e.contains_label(RETRYABLE_WRITE_ERROR)
+        || e.contains_label(TRANSIENT_TRANSACTION_ERROR)
+        || matches!(
+            *e.kind,
+            ErrorKind::Io(_) | ErrorKind::ServerSelection { .. } | ErrorKind::ConnectionPoolCleared { .. }
+        )
+}
+
+/// Only the errors of the driver count against the breaker,
+/// an invalid id or a missing document is the fault of the request
+fn is_driver_error(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<mongodb::error::Error>().is_some()
+}
+
 // orm 基础接口
+#[allow(dead_code)]
 pub trait BaseMapper<T> {
     async fn query_by_id(&self, id: &str) -> anyhow::Result<T>;
     async fn save(&self, t: &T) -> anyhow::Result<ObjectId>;
@@ -86,12 +110,16 @@ impl<T> BaseMapper<T> for DataBase<Collection<T>>
 where
     T: Sync + Send + DeserializeOwned + Serialize + Debug + Clone,
 {
+    #[retryable(retry_on = "is_transient")]
+    #[circuit_breaker("mongo", record_on = "is_driver_error")]
     async fn query_by_id(&self, id: &str) -> anyhow::Result<T> {
         self.find_one(doc! { "_id": ObjectId::parse_str(id)? })
             .await?
-            .ok_or(anyhow::anyhow!("not found"))
+            .ok_or_else(|| NotFound(id.to_string()).into())
     }
 
+    // an insert is not idempotent, so it is not retried
+    #[circuit_breaker("mongo", record_on = "is_driver_error")]
     async fn save(&self, t: &T) -> anyhow::Result<ObjectId> {
         self.insert_one(t)
             .await?
@@ -100,6 +128,7 @@ where
             .ok_or(anyhow::anyhow!("convert error"))
     }
 
+    // the id of a new document is generated once, so a retried upsert can't insert it twice
     async fn update_or_save(&self, t: &T) -> anyhow::Result<ObjectId> {
         let mut document = bson::to_document(t)?;
         let id = document
@@ -109,25 +138,11 @@ where
 
         let id = ObjectId::parse_str(id.as_str().ok_or(anyhow::anyhow!("id not found"))?)?;
         info!("{:?}", id);
-
-        let res = self
-            .update_one(doc! {"_id":id}, doc! { "$set": document })
-            .upsert(true)
-            .await?;
-        info!("{:?}", res);
-
-        // 如果没有upserted_id，说明是更新
-        if res.upserted_id.is_none() {
-            Ok(id)
-        } else {
-            Ok(res
-                .upserted_id
-                .ok_or(anyhow::anyhow!("upsert fail"))?
-                .as_object_id()
-                .ok_or(anyhow::anyhow!("convert error"))?)
-        }
+        self.upsert(id, document).await
     }
 
+    // a retry after a lost acknowledgement would find nothing to delete, so it is not retried
+    #[circuit_breaker("mongo", record_on = "is_driver_error")]
     async fn delete_by_id(&self, id: &str) -> anyhow::Result<()> {
         let result = self
             .delete_one(doc! {"_id":ObjectId::parse_str(id)?})
@@ -138,6 +153,8 @@ where
         Ok(())
     }
 
+    // a retry after a lost acknowledgement would modify nothing, so it is not retried
+    #[circuit_breaker("mongo", record_on = "is_driver_error")]
     async fn update_by_id(&self, id: &str, t: &T) -> anyhow::Result<()> {
         let update = doc! { "$set":  bson::to_document(t)? };
         let result = self
@@ -149,3 +166,20 @@ where
         Ok(())
     }
 }
+
+impl<T> DataBase<Collection<T>>
+where
+    T: Sync + Send + DeserializeOwned + Serialize + Debug + Clone,
+{
+    /// Update or insert the document with the given id, the same id every attempt
+    #[retryable(retry_on = "is_transient")]
+    #[circuit_breaker("mongo", record_on = "is_driver_error")]
+    async fn upsert(&self, id: ObjectId, document: Document) -> anyhow::Result<ObjectId> {
+        let res = self
+            .update_one(doc! {"_id":id}, doc! { "$set": document })
+            .upsert(true)
+            .await?;
+        info!("{:?}", res);
+        Ok(id)
+    }
+}
@@ -4,6 +4,7 @@ use mongodb::bson::doc;
 use mongodb::Collection;
 
 // dao层接口
+#[allow(dead_code)]
 pub trait PostMapper: BaseMapper<BlogPost> {
     async fn get_post(&self, id: &str) -> BlogPost;
     async fn save_post(&self, post: BlogPost) -> BlogPost;
@@ -14,7 +15,7 @@ impl PostMapper for DataBase<Collection<BlogPost>> {
         self.find_one(doc! { "title": id }).await.unwrap().unwrap()
     }
 
-    async fn save_post(&self, post: BlogPost) -> BlogPost {
+    async fn save_post(&self, _post: BlogPost) -> BlogPost {
         // mongodb::bson::serde_helpers::serialize_hex_string_as_object_id();
         todo!()
     }
@@ -6,10 +6,10 @@ mod services;
 
 use springboot::{auto_config, App};
 use springboot_web::WebConfigurator;
-use springboot_web::WebStarter;
 
+// LoggerStarter 和 WebStarter 通过 #[starter] 自动注册
 #[auto_config(WebConfigurator)] // 自动扫描web router
 #[tokio::main]
 async fn main() {
-    App::new().logger(springboot_logger::LoggerStarter).add_starter(WebStarter).run().await
+    App::new().app_info(springboot::app_info!()).run().await
 }
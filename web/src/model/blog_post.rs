@@ -2,20 +2,26 @@ use bon::Builder;
 use chrono::{DateTime, Utc};
 use getset::{CopyGetters, Getters, MutGetters, Setters};
 use mongodb::bson::oid::ObjectId;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
 
 #[derive(
-    Builder, Getters, Setters, MutGetters, CopyGetters, Serialize, Deserialize, Debug, Clone,
+    Builder, Getters, Setters, MutGetters, CopyGetters, Serialize, Deserialize, JsonSchema, Validate, Debug, Clone,
 )]
 #[getset(get = "pub", get_mut = "pub", set = "pub")]
+#[validate(schema(function = "validate_new_post"))]
 pub(crate) struct BlogPost {
     #[serde(
         alias = "_id",
         skip_serializing_if = "Option::is_none",
         serialize_with = "super::serialize_object_id_option_as_hex_string"
     )]
+    #[schemars(with = "Option<String>")]
     id: Option<ObjectId>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    // a partial update leaves the title out, a given title must not be empty
+    #[validate(length(min = 1, message = "标题不能为空"))]
     title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     author: Option<String>,
@@ -32,21 +38,31 @@ pub(crate) struct BlogPost {
         with = "super::datetime_format",
         default = "super::get_local_now"
     )]
+    #[schemars(with = "Option<String>")]
     created_at: Option<DateTime<Utc>>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         with = "super::datetime_format",
         default = "super::get_local_now"
     )]
+    #[schemars(with = "Option<String>")]
     updated_at: Option<DateTime<Utc>>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         with = "super::datetime_format",
         default = "super::get_local_now"
     )]
+    #[schemars(with = "Option<String>")]
     deleted_at: Option<DateTime<Utc>>,
 }
 
+/// A new post, without an id, needs a title
+fn validate_new_post(post: &BlogPost) -> Result<(), ValidationError> {
+    if post.id.is_none() && post.title.is_none() {
+        return Err(ValidationError::new("title_required").with_message("标题不能为空".into()));
+    }
+    Ok(())
+}
 
 #[cfg(test)]
 mod tests {
@@ -80,4 +96,13 @@ mod tests {
         println!("{:?}", post1);
         println!("{}", json!(post1));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_validate() {
+        // a new post needs a title, an existing one can be updated partially
+        assert!(BlogPost::builder().build().validate().is_err());
+        assert!(BlogPost::builder().title("aaa".to_string()).build().validate().is_ok());
+        assert!(BlogPost::builder().id(ObjectId::new()).build().validate().is_ok());
+        assert!(BlogPost::builder().title(String::new()).build().validate().is_err());
+    }
+}
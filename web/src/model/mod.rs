@@ -24,7 +24,7 @@ mod datetime_format {
     use chrono::{DateTime, Local, NaiveDateTime, Utc};
     use serde::{self, Deserialize, Deserializer, Serializer};
 
-    const FORMAT: &'static str = "%Y-%m-%d %H:%M:%S";
+    const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
     // serialize_with 函数的签名必须遵循以下模式：
     //
@@ -1,31 +1,48 @@
-use crate::dao::{get_collection, BaseMapper, DataBase};
+use crate::common::timing::Timing;
+use crate::dao::{BaseMapper, DataBase, MongoService};
 use crate::model::BlogPost;
 use mongodb::Collection;
+use springboot::cache::{cache_evict, cacheable};
+use springboot::component::service::Service;
+use springboot::interceptor::around;
 use springboot_logger::info;
-use std::sync::LazyLock;
 
-// 小写不告警
-#[allow(non_upper_case_globals)]
-#[allow(non_snake_case)]
-static blog_mapper: LazyLock<DataBase<Collection<BlogPost>>> =
-    LazyLock::new(|| get_collection::<BlogPost>());
-
-pub(crate) async fn query(id: String) -> anyhow::Result<BlogPost> {
-    blog_mapper.query_by_id(&id).await
+#[derive(Clone, Service)]
+pub(crate) struct BlogService {
+    #[inject(component)]
+    mongo: MongoService,
 }
 
-pub(crate) async fn save_post(post: BlogPost) -> anyhow::Result<BlogPost> {
-    let id = blog_mapper.save(&post).await?;
-    let mut post = post.clone();
-    info!("{id:?}");
-    // post.set_id(id);
-    // post.id = Some(id);
-    post.set_id(Some(id));
-    Ok(post)
-}
+#[around]
+impl BlogService {
+    fn blog_mapper(&self) -> DataBase<Collection<BlogPost>> {
+        self.mongo.collection::<BlogPost>()
+    }
+
+    #[around(Timing)]
+    #[cacheable("post", key = "id")]
+    pub(crate) async fn query(&self, id: String) -> anyhow::Result<BlogPost> {
+        self.blog_mapper().query_by_id(&id).await
+    }
+
+    #[allow(dead_code)]
+    #[around(Timing)]
+    pub(crate) async fn save_post(&self, post: BlogPost) -> anyhow::Result<BlogPost> {
+        let id = self.blog_mapper().save(&post).await?;
+        let mut post = post.clone();
+        info!("{id:?}");
+        // post.set_id(id);
+        // post.id = Some(id);
+        post.set_id(Some(id));
+        Ok(post)
+    }
 
-pub(crate) async fn update_or_save(mut p: BlogPost) -> anyhow::Result<BlogPost> {
-    let id = blog_mapper.update_or_save(&p).await?;
-    p.set_id(Some(id));
-    Ok(p)
+    // a new post has no id yet, so there is nothing to evict
+    #[around(Timing)]
+    #[cache_evict("post", key = "p.id().map(|id| id.to_hex()).unwrap_or_default()")]
+    pub(crate) async fn update_or_save(&self, mut p: BlogPost) -> anyhow::Result<BlogPost> {
+        let id = self.blog_mapper().update_or_save(&p).await?;
+        p.set_id(Some(id));
+        Ok(p)
+    }
 }